@@ -1,8 +1,18 @@
+use std::fs;
+
+use aoc2021::generate::generate;
+use aoc2021::prelude::*;
 use aoc2021::solutions::*;
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{
+    criterion_group, criterion_main, AxisScale, BenchmarkId, Criterion, PlotConfiguration,
+    Throughput,
+};
+
+// Seed for scaling inputs, fixed so runs are comparable
+const SEED: u64 = 2021;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
-    for day in 1..=25 {
+    for day in days() {
         let input_path = format!("input/day_{:02}.txt", day);
         let runner = match get_runner(day, input_path) {
             Ok(r) => r,
@@ -22,5 +32,78 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, criterion_benchmark);
+// Parsing each day's input, held in memory so reading the file isn't measured
+pub fn parse_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (day, input) in inputs() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(day), &input, |b, input| {
+            b.iter(|| get_runner_from_reader(day, input.as_bytes()))
+        });
+    }
+    group.finish();
+}
+
+// Parsing and solving both parts, as `aoc2021 <day>` does
+pub fn end_to_end_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("end to end");
+    let options = RunOptions::default();
+    for (day, input) in inputs() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(day), &input, |b, input| {
+            b.iter(|| solve_str(day, input, &options))
+        });
+    }
+    group.finish();
+}
+
+// Parsing and solving generated inputs of increasing size, to show how each day scales
+pub fn scaling_benchmark(c: &mut Criterion) {
+    let options = RunOptions::default();
+    for day in days() {
+        let (sizes, parts) = scaling(day);
+        let mut group = c.benchmark_group(format!("day {:02} scaling", day));
+        group.sample_size(10);
+        group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+        for &size in sizes {
+            let input = generate(day, SEED, size).unwrap();
+            group.throughput(Throughput::Bytes(input.len() as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(size), &input, |b, input| {
+                b.iter(|| solve_parts(day, input.as_bytes(), parts, &options))
+            });
+        }
+        group.finish();
+    }
+}
+
+// Generated input sizes for a day, and the parts that finish on any generated input
+fn scaling(day: usize) -> (&'static [usize], &'static [Part]) {
+    match day {
+        // Random octopi may never synchronize
+        11 => (&[10, 20, 40, 80], &[Part::One]),
+        // Paths grow exponentially with the number of caves
+        12 => (&[2, 4, 6, 8, 10], &Part::ALL),
+        // Grid sides, target distances and quadratic pairs of numbers
+        9 | 15 | 17 | 18 => (&[25, 50, 100, 200], &Part::ALL),
+        _ => (&[100, 200, 400, 800, 1600], &Part::ALL),
+    }
+}
+
+// Puzzle inputs of the days that have an input file
+fn inputs() -> Vec<(usize, String)> {
+    days()
+        .filter_map(|day| {
+            let input = fs::read_to_string(format!("input/day_{:02}.txt", day)).ok()?;
+            Some((day, input))
+        })
+        .collect()
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark,
+    parse_benchmark,
+    end_to_end_benchmark,
+    scaling_benchmark
+);
 criterion_main!(benches);
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    generate_registry(Path::new(&manifest_dir), Path::new(&out_dir));
+    generate_answer_tests(Path::new(&manifest_dir), Path::new(&out_dir));
+    generate_examples(Path::new(&manifest_dir), Path::new(&out_dir));
+}
+
+// Generate the day modules and registry from the `day_NN.rs` files in `src/solutions`, so adding
+// a day only requires adding its module file. `cargo fmt` can't see modules declared here, so
+// `cargo xtask fmt` formats them instead.
+fn generate_registry(manifest_dir: &Path, out_dir: &Path) {
+    let solutions_dir = manifest_dir.join("src").join("solutions");
+    println!("cargo:rerun-if-changed={}", solutions_dir.display());
+
+    let mut days: Vec<(usize, String)> = fs::read_dir(&solutions_dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let num = name.strip_prefix("day_")?.strip_suffix(".rs")?;
+            if num.len() != 2 {
+                return None;
+            }
+            Some((num.parse().ok()?, name))
+        })
+        .collect();
+    days.sort();
+
+    let mut out = String::new();
+    for (day, name) in &days {
+        writeln!(
+            out,
+            "#[path = {:?}]\nmod day_{:02};",
+            solutions_dir.join(name).display().to_string(),
+            day
+        )
+        .unwrap();
+    }
+    writeln!(out, "\nstatic DAYS: &[DayEntry] = &[").unwrap();
+    for (day, _) in &days {
+        writeln!(
+            out,
+            "    DayEntry {{ day: {0}, new: new_runner::<day_{0:02}::Day{0:02}>, \
+             algorithms: <day_{0:02}::Day{0:02} as Day>::algorithms, \
+             generate: <day_{0:02}::Day{0:02} as Generate>::generate }},",
+            day
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    fs::write(out_dir.join("days.rs"), out).unwrap();
+}
+
+// Generate one integration test per entry in the expected answers file
+fn generate_answer_tests(manifest_dir: &Path, out_dir: &Path) {
+    let answers_path = manifest_dir.join("answers.txt");
+    println!("cargo:rerun-if-changed={}", answers_path.display());
+
+    let mut out = String::new();
+    let answers = fs::read_to_string(answers_path).unwrap_or_default();
+    for line in answers.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut split = line.split(' ');
+        if let (Some(Ok(day)), Some(Ok(part))) = (
+            split.next().map(str::parse::<usize>),
+            split.next().map(str::parse::<usize>),
+        ) {
+            writeln!(
+                out,
+                "#[test]\nfn day_{0:02}_part_{1}() {{\n    check({0}, {1});\n}}",
+                day, part
+            )
+            .unwrap();
+        }
+    }
+
+    fs::write(out_dir.join("answer_tests.rs"), out).unwrap();
+}
+
+// Embed the examples in `fixtures/day_NN/NAME.txt` with their answers from `fixtures/answers.txt`,
+// and generate one integration test per example
+fn generate_examples(manifest_dir: &Path, out_dir: &Path) {
+    let fixtures_dir = manifest_dir.join("fixtures");
+    println!("cargo:rerun-if-changed={}", fixtures_dir.display());
+
+    // Answers keyed by (day, example name)
+    let mut answers: BTreeMap<(usize, String), Vec<(usize, String)>> = BTreeMap::new();
+    let text = fs::read_to_string(fixtures_dir.join("answers.txt")).unwrap_or_default();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut split = line.splitn(4, ' ');
+        if let (Some(Ok(day)), Some(name), Some(Ok(part)), Some(value)) = (
+            split.next().map(str::parse::<usize>),
+            split.next(),
+            split.next().map(str::parse::<usize>),
+            split.next(),
+        ) {
+            answers
+                .entry((day, name.into()))
+                .or_default()
+                .push((part, value.into()));
+        }
+    }
+
+    let mut examples = Vec::new();
+    for entry in fs::read_dir(&fixtures_dir).into_iter().flatten() {
+        let dir = entry.unwrap().path();
+        let day = match dir
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("day_")?.parse::<usize>().ok())
+        {
+            Some(day) => day,
+            None => continue,
+        };
+        for file in fs::read_dir(&dir).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+                continue;
+            }
+            let name = path.file_stem().unwrap().to_str().unwrap().to_owned();
+            examples.push((day, name, path));
+        }
+    }
+    // Numbered examples sort numerically
+    examples.sort_by(|(d1, n1, _), (d2, n2, _)| (d1, n1.len(), n1).cmp(&(d2, n2.len(), n2)));
+
+    let mut registry = String::from("static EXAMPLES: &[Example] = &[\n");
+    let mut tests = String::new();
+    for (day, name, path) in &examples {
+        let parts = answers.remove(&(*day, name.clone())).unwrap_or_default();
+        writeln!(
+            registry,
+            "    Example {{ day: {}, name: {:?}, input: include_str!({:?}), answers: &{:?} }},",
+            day,
+            name,
+            path.display().to_string(),
+            parts
+        )
+        .unwrap();
+        writeln!(
+            tests,
+            "#[test]\nfn day_{0:02}_example_{2}() {{\n    check({0}, {1:?});\n}}",
+            day,
+            name,
+            name.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        )
+        .unwrap();
+    }
+    writeln!(registry, "];").unwrap();
+    if let Some(((day, name), _)) = answers.into_iter().next() {
+        panic!(
+            "Answers for missing example fixtures/day_{:02}/{}.txt",
+            day, name
+        );
+    }
+
+    fs::write(out_dir.join("examples.rs"), registry).unwrap();
+    fs::write(out_dir.join("example_tests.rs"), tests).unwrap();
+}
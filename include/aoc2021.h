@@ -0,0 +1,148 @@
+/* C API for the aoc2021 solvers, see src/ffi.rs */
+
+#ifndef AOC2021_H
+#define AOC2021_H
+
+/* Generated by `cargo xtask header`, don't edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Kind of value held by an answer
+ */
+typedef enum Aoc2021AnswerKind {
+  /**
+   * Fits in a 64-bit signed integer
+   */
+  AOC2021_ANSWER_KIND_INTEGER = 0,
+  /**
+   * Integer too large for 64 bits, only available as text
+   */
+  AOC2021_ANSWER_KIND_BIG_INTEGER = 1,
+  AOC2021_ANSWER_KIND_TEXT = 2,
+  /**
+   * Rows of characters, such as letters drawn with blocks
+   */
+  AOC2021_ANSWER_KIND_PRINTABLE = 3,
+  /**
+   * The part has no solution for this input
+   */
+  AOC2021_ANSWER_KIND_NONE = 4,
+  /**
+   * The part was stopped before it finished
+   */
+  AOC2021_ANSWER_KIND_TIMEOUT = 5,
+  /**
+   * The part failed, the text is the error message
+   */
+  AOC2021_ANSWER_KIND_ERROR = 6,
+} Aoc2021AnswerKind;
+
+/**
+ * Answer to one part
+ */
+typedef struct Aoc2021Answer Aoc2021Answer;
+
+/**
+ * Parsed input for a day, ready to solve its parts
+ */
+typedef struct Aoc2021Runner Aoc2021Runner;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Message for the last error on this thread, or NULL if there hasn't been one. The string is
+ * valid until the next failing call on the same thread.
+ */
+const char *aoc2021_last_error(void);
+
+/**
+ * Parse `len` bytes of puzzle input for a day. Returns NULL if the day doesn't exist or the
+ * input can't be parsed.
+ *
+ * # Safety
+ *
+ * `input` must point to `len` readable bytes, it may be NULL if `len` is 0.
+ */
+struct Aoc2021Runner *aoc2021_runner_new(uint32_t day, const uint8_t *input, size_t len);
+
+/**
+ * Solve part 1 or 2. Returns NULL if the runner is NULL or the part doesn't exist, failures
+ * while solving are returned as answers of kind `Error`.
+ *
+ * # Safety
+ *
+ * `runner` must be NULL or a runner from `aoc2021_runner_new` that hasn't been freed.
+ */
+struct Aoc2021Answer *aoc2021_run_part(const struct Aoc2021Runner *runner, uint32_t part);
+
+/**
+ * Kind of value the answer holds
+ *
+ * # Safety
+ *
+ * `answer` must be an answer from `aoc2021_run_part` that hasn't been freed.
+ */
+enum Aoc2021AnswerKind aoc2021_answer_kind(const struct Aoc2021Answer *answer);
+
+/**
+ * Store an answer of kind `Integer` in `out`, returning false for other kinds
+ *
+ * # Safety
+ *
+ * `answer` must be an answer from `aoc2021_run_part` that hasn't been freed, and `out` must be
+ * writable.
+ */
+bool aoc2021_answer_integer(const struct Aoc2021Answer *answer, int64_t *out);
+
+/**
+ * Answer as NUL-terminated UTF-8, such as the digits of an integer, the rows of a printable
+ * answer or the message of an error. Returns NULL for `None` and `Timeout` answers. The string
+ * is owned by the answer.
+ *
+ * # Safety
+ *
+ * `answer` must be an answer from `aoc2021_run_part` that hasn't been freed.
+ */
+const char *aoc2021_answer_text(const struct Aoc2021Answer *answer);
+
+/**
+ * Raw bytes of a `Text` or `Printable` answer, storing their length in `len`. Returns NULL for
+ * other kinds. The bytes are owned by the answer.
+ *
+ * # Safety
+ *
+ * `answer` must be an answer from `aoc2021_run_part` that hasn't been freed, and `len` must be
+ * writable.
+ */
+const uint8_t *aoc2021_answer_bytes(const struct Aoc2021Answer *answer, size_t *len);
+
+/**
+ * Free an answer, NULL is ignored
+ *
+ * # Safety
+ *
+ * `answer` must be NULL or an answer from `aoc2021_run_part` that hasn't been freed.
+ */
+void aoc2021_answer_free(struct Aoc2021Answer *answer);
+
+/**
+ * Free a runner, NULL is ignored. Answers from the runner stay valid.
+ *
+ * # Safety
+ *
+ * `runner` must be NULL or a runner from `aoc2021_runner_new` that hasn't been freed.
+ */
+void aoc2021_runner_free(struct Aoc2021Runner *runner);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* AOC2021_H */
@@ -0,0 +1,131 @@
+//! Expected answers for the puzzle inputs in `input/`.
+//!
+//! The answers file has one answer per line in the form `DD P VALUE`, where `VALUE` is an
+//! integer of any size, `none`, a quoted string for printable answers, or `text` followed by a
+//! quoted string for text answers. Blank lines and lines starting with `#` are ignored.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::prelude::*;
+
+pub const ANSWERS_PATH: &str = "answers.txt";
+
+/// Expected answers keyed by (day, part)
+pub type Answers = BTreeMap<(usize, usize), Answer>;
+
+pub fn read_answers(path: impl AsRef<Path>) -> Result<Answers, AOCError> {
+    let text = fs::read_to_string(&path).map_err(AOCError::BadInputFile)?;
+    parse_answers(&text).map_err(|e| e.with_path(path))
+}
+
+pub fn write_answers(path: impl AsRef<Path>, answers: &Answers) -> Result<(), AOCError> {
+    let mut text = String::from("# day part answer\n");
+    for ((day, part), answer) in answers {
+        if let Some(value) = format_answer(answer) {
+            writeln!(text, "{:02} {} {}", day, part, value).unwrap();
+        }
+    }
+    fs::write(path, text).map_err(AOCError::BadInputFile)
+}
+
+pub fn parse_answers(text: &str) -> Result<Answers, AOCError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| parse_line(line).map_err(|e| e.line(i).into()))
+        .collect()
+}
+
+fn parse_line(line: &str) -> Result<((usize, usize), Answer), ParseError> {
+    let mut split = line.splitn(3, ' ');
+    let day = split
+        .next()
+        .ok_or_else(|| ParseError::new(line, 0, "a day"))?;
+    let day = parse_token(line, day, "a day")?;
+    let part = split
+        .next()
+        .ok_or_else(|| ParseError::new(line, line.chars().count(), "a part"))?;
+    let part = parse_token(line, part, "a part")?;
+    let value = split
+        .next()
+        .ok_or_else(|| ParseError::new(line, line.chars().count(), "an answer"))?;
+    Ok(((day, part), parse_answer(line, value)?))
+}
+
+pub(crate) fn parse_answer(line: &str, s: &str) -> Result<Answer, ParseError> {
+    if s == "none" {
+        return Ok(Answer::None);
+    }
+    if let Some(quoted) = s.strip_prefix("text ") {
+        return Ok(Answer::Text(unquote(line, quoted)?));
+    }
+    if s.starts_with('"') {
+        return Ok(Answer::Printable(unquote(line, s)?.into_bytes()));
+    }
+
+    // Integers use the smallest variant that holds them
+    if let Ok(n) = s.parse() {
+        return Ok(Answer::Integer(n));
+    }
+    if let Ok(n) = s.parse() {
+        return Ok(Answer::Signed(n));
+    }
+    parse_token(line, s, "an integer, none or a quoted string").map(Answer::BigInteger)
+}
+
+pub(crate) fn unquote(line: &str, s: &str) -> Result<String, ParseError> {
+    let quoted = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| ParseError::token(line, s, "a quoted string"))?;
+    let mut unescaped = String::with_capacity(quoted.len());
+    let mut chars = quoted.char_indices();
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, 'n')) => unescaped.push('\n'),
+                Some((_, c @ ('\\' | '"'))) => unescaped.push(c),
+                Some((j, c)) => {
+                    let token = &quoted[j..j + c.len_utf8()];
+                    return Err(ParseError::token(line, token, "an escape sequence"));
+                }
+                None => return Err(ParseError::token(line, s, "an escape sequence")),
+            },
+            c => unescaped.push(c),
+        }
+    }
+    Ok(unescaped)
+}
+
+/// Format an answer as it appears in the answers file, errors and timeouts have no representation
+pub fn format_answer(answer: &Answer) -> Option<String> {
+    match answer {
+        Answer::Integer(n) => Some(n.to_string()),
+        Answer::Signed(n) => Some(n.to_string()),
+        Answer::BigInteger(n) => Some(n.to_string()),
+        Answer::Text(t) => Some(format!("text {}", quote(t))),
+        Answer::Printable(p) => Some(quote(&String::from_utf8_lossy(p))),
+        Answer::None => Some("none".into()),
+        Answer::Timeout | Answer::Error(_) => None,
+    }
+}
+
+pub(crate) fn quote(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\\' | '"' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
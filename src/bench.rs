@@ -0,0 +1,344 @@
+//! Benchmarks from the CLI, with a history of runs to compare against.
+//!
+//! The history file is a JSON array of runs, oldest first. Each run records the git revision it
+//! was taken at, an optional baseline name, and the time statistics of each part. Parts are
+//! compared by their median time, which is less sensitive to outliers than the mean.
+
+use std::fs;
+use std::hint::black_box;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::prelude::*;
+use crate::solutions::get_runner_from_reader;
+
+pub const HISTORY_PATH: &str = "bench_history.json";
+
+/// How long to time each part
+#[derive(Clone, Copy, Debug)]
+pub struct TimerConfig {
+    /// Time spent running a part before measuring it, at least one run
+    pub warm_up: Duration,
+    /// Time spent measuring a part, unless the sample limits are reached first
+    pub measure: Duration,
+    /// Shortest time per sample, fast parts run several times per sample so timer overhead
+    /// doesn't dominate
+    pub sample_time: Duration,
+    pub min_samples: usize,
+    pub max_samples: usize,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            warm_up: Duration::from_millis(200),
+            measure: Duration::from_secs(1),
+            sample_time: Duration::from_micros(10),
+            min_samples: 10,
+            max_samples: 10_000,
+        }
+    }
+}
+
+/// Summary of the times of repeated runs
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Stats {
+    pub samples: usize,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Statistics of a non-empty set of times
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        let median = match n % 2 {
+            0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+            _ => sorted[n / 2],
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = sorted
+            .iter()
+            .map(|t| (t.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        Self {
+            samples: n,
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+            min: sorted[0],
+            max: sorted[n - 1],
+        }
+    }
+}
+
+/// Time of one part of a day
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PartBench {
+    pub day: usize,
+    pub part: Part,
+    pub stats: Stats,
+}
+
+/// Benchmark results taken at one revision
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BenchRun {
+    /// Git revision, with a `-dirty` suffix if there were uncommitted changes
+    pub rev: String,
+    /// Baseline name to compare later runs against
+    pub name: Option<String>,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub results: Vec<PartBench>,
+}
+
+impl BenchRun {
+    /// Run at the current revision and time
+    pub fn new(name: Option<String>, results: Vec<PartBench>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |t| t.as_secs());
+        Self {
+            rev: git_revision(),
+            name,
+            timestamp,
+            results,
+        }
+    }
+
+    pub fn get(&self, day: usize, part: Part) -> Option<&Stats> {
+        self.results
+            .iter()
+            .find(|r| r.day == day && r.part == part)
+            .map(|r| &r.stats)
+    }
+}
+
+/// Change in a part's median time since a baseline
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub day: usize,
+    pub part: Part,
+    pub stats: Stats,
+    /// Median time in the baseline, if the baseline has the part
+    pub baseline: Option<Duration>,
+    /// Relative change in median time, 0.1 is 10% slower
+    pub change: Option<f64>,
+    /// Whether the part got slower by more than the threshold
+    pub regressed: bool,
+}
+
+/// Time runs of `f` until the config's time or sample limits are reached, each sample is the
+/// mean time of a batch of runs
+pub fn measure(config: &TimerConfig, mut f: impl FnMut()) -> Stats {
+    let start = Instant::now();
+    let mut runs = 0;
+    while runs == 0 || start.elapsed() < config.warm_up {
+        f();
+        runs += 1;
+    }
+
+    // Estimate the time per run from the warm-up to size the batches
+    let per_run = start.elapsed() / runs;
+    let batch = match per_run.is_zero() {
+        true => 1,
+        false => {
+            (config.sample_time.as_nanos() / per_run.as_nanos()).clamp(1, u32::MAX as u128) as u32
+        }
+    };
+
+    let mut samples = Vec::new();
+    let start = Instant::now();
+    while samples.len() < config.min_samples
+        || (samples.len() < config.max_samples && start.elapsed() < config.measure)
+    {
+        let sample = Instant::now();
+        for _ in 0..batch {
+            f();
+        }
+        samples.push(sample.elapsed() / batch);
+    }
+    Stats::from_samples(&samples)
+}
+
+/// Parse a day's input once, then time each of the given parts
+pub fn bench_day(
+    day: usize,
+    input: &str,
+    parts: &[Part],
+    config: &TimerConfig,
+) -> Result<Vec<PartBench>, AOCError> {
+    let runner = get_runner_from_reader(day, input.as_bytes())?;
+    Ok(parts
+        .iter()
+        .map(|&part| PartBench {
+            day,
+            part,
+            stats: measure(config, || {
+                black_box(runner.part(part));
+            }),
+        })
+        .collect())
+}
+
+/// Most recent run with a baseline name, or else at a revision starting with `name`
+pub fn find_baseline<'a>(history: &'a [BenchRun], name: &str) -> Option<&'a BenchRun> {
+    let named = history
+        .iter()
+        .rev()
+        .find(|run| run.name.as_deref() == Some(name));
+    named.or_else(|| history.iter().rev().find(|run| run.rev.starts_with(name)))
+}
+
+/// Compare each part of a run with a baseline, flagging parts more than `threshold` percent
+/// slower
+pub fn compare(run: &BenchRun, baseline: Option<&BenchRun>, threshold: f64) -> Vec<Comparison> {
+    run.results
+        .iter()
+        .map(|result| {
+            let baseline = baseline
+                .and_then(|b| b.get(result.day, result.part))
+                .map(|stats| stats.median);
+            let change = baseline
+                .filter(|b| !b.is_zero())
+                .map(|b| result.stats.median.as_secs_f64() / b.as_secs_f64() - 1.0);
+            Comparison {
+                day: result.day,
+                part: result.part,
+                stats: result.stats,
+                baseline,
+                change,
+                regressed: change.is_some_and(|c| c * 100.0 > threshold),
+            }
+        })
+        .collect()
+}
+
+/// Current git revision, or "unknown" outside a git checkout
+pub fn git_revision() -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|rev| rev.trim().to_owned())
+        .filter(|rev| !rev.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/// Read the runs in a history file, a missing file has no runs
+pub fn read_history(path: impl AsRef<Path>) -> Result<Vec<BenchRun>, AOCError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AOCError::BadInputFile(e)),
+    };
+    parse_history(&text)
+}
+
+pub fn write_history(path: impl AsRef<Path>, history: &[BenchRun]) -> Result<(), AOCError> {
+    let runs: Vec<_> = history.iter().map(run_value).collect();
+    let mut text = serde_json::to_string_pretty(&runs).unwrap();
+    text.push('\n');
+    fs::write(path, text).map_err(AOCError::BadInputFile)
+}
+
+pub fn parse_history(text: &str) -> Result<Vec<BenchRun>, AOCError> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|e| AOCError::BadHistory(e.to_string()))?;
+    value
+        .as_array()
+        .ok_or_else(|| bad_history("expected an array of runs"))?
+        .iter()
+        .map(parse_run)
+        .collect()
+}
+
+fn run_value(run: &BenchRun) -> Value {
+    let results: Vec<_> = run
+        .results
+        .iter()
+        .map(|r| {
+            json!({
+                "day": r.day,
+                "part": r.part.number(),
+                "samples": r.stats.samples,
+                "median_ns": r.stats.median.as_nanos() as u64,
+                "mean_ns": r.stats.mean.as_nanos() as u64,
+                "stddev_ns": r.stats.stddev.as_nanos() as u64,
+                "min_ns": r.stats.min.as_nanos() as u64,
+                "max_ns": r.stats.max.as_nanos() as u64,
+            })
+        })
+        .collect();
+    json!({
+        "rev": run.rev,
+        "name": run.name,
+        "timestamp": run.timestamp,
+        "results": results,
+    })
+}
+
+fn parse_run(value: &Value) -> Result<BenchRun, AOCError> {
+    let rev = value["rev"]
+        .as_str()
+        .ok_or_else(|| bad_history("expected a revision"))?;
+    let name = match &value["name"] {
+        Value::Null => None,
+        name => Some(
+            name.as_str()
+                .ok_or_else(|| bad_history("expected a baseline name"))?
+                .to_owned(),
+        ),
+    };
+    let results = value["results"]
+        .as_array()
+        .ok_or_else(|| bad_history("expected an array of results"))?
+        .iter()
+        .map(parse_result)
+        .collect::<Result<_, _>>()?;
+    Ok(BenchRun {
+        rev: rev.into(),
+        name,
+        timestamp: field(value, "timestamp")?,
+        results,
+    })
+}
+
+fn parse_result(value: &Value) -> Result<PartBench, AOCError> {
+    let nanos = |key| field(value, key).map(Duration::from_nanos);
+    Ok(PartBench {
+        day: field(value, "day")? as usize,
+        part: field(value, "part")?.to_string().parse()?,
+        stats: Stats {
+            samples: field(value, "samples")? as usize,
+            median: nanos("median_ns")?,
+            mean: nanos("mean_ns")?,
+            stddev: nanos("stddev_ns")?,
+            min: nanos("min_ns")?,
+            max: nanos("max_ns")?,
+        },
+    })
+}
+
+fn field(value: &Value, key: &str) -> Result<u64, AOCError> {
+    value[key]
+        .as_u64()
+        .ok_or_else(|| bad_history(&format!("expected an integer {:?}", key)))
+}
+
+fn bad_history(message: &str) -> AOCError {
+    AOCError::BadHistory(message.into())
+}
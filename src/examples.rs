@@ -0,0 +1,52 @@
+//! Puzzle examples with their expected answers, shared by the tests and the `--example` flag.
+//!
+//! Each example is a file `fixtures/day_NN/NAME.txt`, embedded at build time. Expected answers
+//! are in `fixtures/answers.txt`, one per line in the form `DD NAME P VALUE`, where `VALUE` is
+//! written as in the answers file. Examples may have an answer for only one part.
+
+use crate::answers::parse_answer;
+use crate::prelude::*;
+
+// The `EXAMPLES` list, generated by build.rs from the files in `fixtures/`
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+#[derive(Debug)]
+pub struct Example {
+    pub day: usize,
+    pub name: &'static str,
+    pub input: &'static str,
+    /// Part numbers and answers in the answers file syntax
+    answers: &'static [(usize, &'static str)],
+}
+
+impl Example {
+    /// Expected answer for a part, if the example has one
+    pub fn answer(&self, part: Part) -> Option<Answer> {
+        let (_, value) = self.answers.iter().find(|(p, _)| *p == part.number())?;
+        let answer = parse_answer(value, value).unwrap_or_else(|e| {
+            panic!(
+                "Bad answer for day {} example {}: {}",
+                self.day, self.name, e
+            )
+        });
+        Some(answer)
+    }
+}
+
+/// Examples for a day, in order
+pub fn examples(day: usize) -> impl Iterator<Item = &'static Example> {
+    EXAMPLES.iter().filter(move |example| example.day == day)
+}
+
+/// Example of a day by name, or the first example if no name is given
+pub fn example(day: usize, name: Option<&str>) -> Result<&'static Example, AOCError> {
+    let mut examples = examples(day);
+    let found = match name {
+        Some(name) => examples.find(|example| example.name == name),
+        None => examples.next(),
+    };
+    found.ok_or_else(|| {
+        let names = self::examples(day).map(|example| example.name).collect();
+        AOCError::UnknownExample(day, name.unwrap_or_default().into(), names)
+    })
+}
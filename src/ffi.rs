@@ -0,0 +1,265 @@
+//! C API for embedding the solvers, built as a cdylib with the `ffi` feature by
+//! `cargo xtask cdylib`.
+//!
+//! A runner is created from a day number and an input buffer, then each call to
+//! `aoc2021_run_part` returns an answer to read as an integer, text or bytes. Runners and answers
+//! are freed with their `_free` functions. Functions that fail return NULL or false and record a
+//! message for `aoc2021_last_error`. The header `include/aoc2021.h` is generated from this file
+//! with `cargo xtask header`.
+//!
+//! Panics in a solver are reported as errors in dev builds, but abort the process in release
+//! builds since they're compiled with `panic = "abort"`.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use crate::prelude::*;
+use crate::solutions::get_runner_from_reader;
+
+/// Parsed input for a day, ready to solve its parts
+pub struct Aoc2021Runner {
+    runner: Box<dyn Day>,
+}
+
+/// Answer to one part
+pub struct Aoc2021Answer {
+    answer: Answer,
+    /// Text form returned by `aoc2021_answer_text`, if the answer has one
+    text: Option<CString>,
+}
+
+/// Kind of value held by an answer
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Aoc2021AnswerKind {
+    /// Fits in a 64-bit signed integer
+    Integer = 0,
+    /// Integer too large for 64 bits, only available as text
+    BigInteger = 1,
+    Text = 2,
+    /// Rows of characters, such as letters drawn with blocks
+    Printable = 3,
+    /// The part has no solution for this input
+    None = 4,
+    /// The part was stopped before it finished
+    Timeout = 5,
+    /// The part failed, the text is the error message
+    Error = 6,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl ToString) {
+    let message = message.to_string().replace('\0', " ");
+    LAST_ERROR.with(|e| *e.borrow_mut() = CString::new(message).ok());
+}
+
+// Run `f`, turning a panic into an error message
+fn catch<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".into());
+        Err(format!("Solver panicked: {}", message))
+    })
+}
+
+/// Message for the last error on this thread, or NULL if there hasn't been one. The string is
+/// valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn aoc2021_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// Parse `len` bytes of puzzle input for a day. Returns NULL if the day doesn't exist or the
+/// input can't be parsed.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, it may be NULL if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_runner_new(
+    day: u32,
+    input: *const u8,
+    len: usize,
+) -> *mut Aoc2021Runner {
+    let input = match input.is_null() {
+        true => &[][..],
+        false => slice::from_raw_parts(input, len),
+    };
+    let result = catch(|| {
+        get_runner_from_reader(day as usize, input)
+            .map(|runner| Aoc2021Runner { runner })
+            .map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(runner) => Box::into_raw(Box::new(runner)),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Solve part 1 or 2. Returns NULL if the runner is NULL or the part doesn't exist, failures
+/// while solving are returned as answers of kind `Error`.
+///
+/// # Safety
+///
+/// `runner` must be NULL or a runner from `aoc2021_runner_new` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_run_part(
+    runner: *const Aoc2021Runner,
+    part: u32,
+) -> *mut Aoc2021Answer {
+    let Some(runner) = runner.as_ref() else {
+        set_last_error("Runner is NULL");
+        return ptr::null_mut();
+    };
+    let part = match part {
+        1 => Part::One,
+        2 => Part::Two,
+        _ => {
+            set_last_error(AOCError::BadPart(part.to_string()));
+            return ptr::null_mut();
+        }
+    };
+    let ctx = PartContext {
+        part: Some(part),
+        ..PartContext::default()
+    };
+    let answer = catch(|| Ok(runner.runner.run_part(part, &ctx)))
+        .unwrap_or_else(|e| Answer::Error(Box::new(AOCError::Child(e))));
+    let text = match &answer {
+        Answer::Integer(n) => Some(n.to_string()),
+        Answer::Signed(n) => Some(n.to_string()),
+        Answer::BigInteger(n) => Some(n.to_string()),
+        Answer::Text(t) => Some(t.clone()),
+        Answer::Printable(p) => Some(String::from_utf8_lossy(p).into_owned()),
+        Answer::Error(e) => Some(e.to_string()),
+        Answer::None | Answer::Timeout => None,
+    };
+    Box::into_raw(Box::new(Aoc2021Answer {
+        answer,
+        text: text.and_then(|t| CString::new(t).ok()),
+    }))
+}
+
+/// Kind of value the answer holds
+///
+/// # Safety
+///
+/// `answer` must be an answer from `aoc2021_run_part` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_answer_kind(answer: *const Aoc2021Answer) -> Aoc2021AnswerKind {
+    match &(*answer).answer {
+        Answer::Integer(n) if i64::try_from(*n).is_ok() => Aoc2021AnswerKind::Integer,
+        Answer::Signed(_) => Aoc2021AnswerKind::Integer,
+        Answer::BigInteger(n) if i64::try_from(n).is_ok() => Aoc2021AnswerKind::Integer,
+        Answer::Integer(_) | Answer::BigInteger(_) => Aoc2021AnswerKind::BigInteger,
+        Answer::Text(_) => Aoc2021AnswerKind::Text,
+        Answer::Printable(_) => Aoc2021AnswerKind::Printable,
+        Answer::None => Aoc2021AnswerKind::None,
+        Answer::Timeout => Aoc2021AnswerKind::Timeout,
+        Answer::Error(_) => Aoc2021AnswerKind::Error,
+    }
+}
+
+/// Store an answer of kind `Integer` in `out`, returning false for other kinds
+///
+/// # Safety
+///
+/// `answer` must be an answer from `aoc2021_run_part` that hasn't been freed, and `out` must be
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_answer_integer(
+    answer: *const Aoc2021Answer,
+    out: *mut i64,
+) -> bool {
+    let value = match &(*answer).answer {
+        Answer::Integer(n) => i64::try_from(*n).ok(),
+        Answer::Signed(n) => Some(*n as i64),
+        Answer::BigInteger(n) => i64::try_from(n).ok(),
+        _ => None,
+    };
+    match value {
+        Some(value) => {
+            *out = value;
+            true
+        }
+        None => {
+            set_last_error(format!(
+                "Answer is not a 64-bit integer: {:?}",
+                (*answer).answer
+            ));
+            false
+        }
+    }
+}
+
+/// Answer as NUL-terminated UTF-8, such as the digits of an integer, the rows of a printable
+/// answer or the message of an error. Returns NULL for `None` and `Timeout` answers. The string
+/// is owned by the answer.
+///
+/// # Safety
+///
+/// `answer` must be an answer from `aoc2021_run_part` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_answer_text(answer: *const Aoc2021Answer) -> *const c_char {
+    (*answer).text.as_ref().map_or(ptr::null(), |s| s.as_ptr())
+}
+
+/// Raw bytes of a `Text` or `Printable` answer, storing their length in `len`. Returns NULL for
+/// other kinds. The bytes are owned by the answer.
+///
+/// # Safety
+///
+/// `answer` must be an answer from `aoc2021_run_part` that hasn't been freed, and `len` must be
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_answer_bytes(
+    answer: *const Aoc2021Answer,
+    len: *mut usize,
+) -> *const u8 {
+    let bytes = match &(*answer).answer {
+        Answer::Text(t) => t.as_bytes(),
+        Answer::Printable(p) => p.as_slice(),
+        _ => {
+            *len = 0;
+            return ptr::null();
+        }
+    };
+    *len = bytes.len();
+    bytes.as_ptr()
+}
+
+/// Free an answer, NULL is ignored
+///
+/// # Safety
+///
+/// `answer` must be NULL or an answer from `aoc2021_run_part` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_answer_free(answer: *mut Aoc2021Answer) {
+    if !answer.is_null() {
+        drop(Box::from_raw(answer));
+    }
+}
+
+/// Free a runner, NULL is ignored. Answers from the runner stay valid.
+///
+/// # Safety
+///
+/// `runner` must be NULL or a runner from `aoc2021_runner_new` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_runner_free(runner: *mut Aoc2021Runner) {
+    if !runner.is_null() {
+        drop(Box::from_raw(runner));
+    }
+}
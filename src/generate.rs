@@ -0,0 +1,65 @@
+//! Random puzzle inputs for stress testing and benchmarking the solutions.
+//!
+//! Generators are deterministic, so the same day, seed and size always give the same input.
+
+use std::ops::Range;
+
+use crate::prelude::*;
+use crate::solutions;
+
+/// Small pseudo-random number generator (SplitMix64), so inputs don't change with a dependency
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in a non-empty range
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as usize
+    }
+
+    /// Uniform signed value in a non-empty range
+    pub fn range_signed(&mut self, range: Range<isize>) -> isize {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        let len = range.end.abs_diff(range.start);
+        range.start + (self.next_u64() % len as u64) as isize
+    }
+
+    /// True with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    /// Random element of a non-empty slice
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i + 1));
+        }
+    }
+}
+
+/// Random input generator for a day
+pub trait Generate {
+    /// Structurally valid puzzle input, growing with `size`
+    fn generate(rng: &mut Rng, size: usize) -> String;
+}
+
+/// Generate puzzle input for a day from a seed
+pub fn generate(day: usize, seed: u64, size: usize) -> Result<String, AOCError> {
+    solutions::generate_input(day, &mut Rng::new(seed), size)
+}
@@ -0,0 +1,445 @@
+//! Running days in child processes of the CLI binary, so a crash only fails that day.
+//!
+//! The child is started as `<exe> child <day> [options]` and reports on stdout, one record per
+//! line:
+//!
+//! ```text
+//! read_ns N
+//! parse_ns N
+//! skipped N
+//! part P TIME_NS VALUE
+//! ```
+//!
+//! or a single record if the day could not be solved, either `error KIND "MESSAGE"` or
+//! `parse_error LINE COLUMN "TEXT" "EXPECTED" "FOUND"` for input that could not be parsed. `KIND`
+//! lets the parent rebuild errors it handles specially, such as `no_input`, and is `other` for
+//! the rest.
+//! `VALUE` is an answer as written in the answers file, `signed N` or `big N` for integers that
+//! keep a wider kind, `timeout`, or `error "MESSAGE"`.
+//!
+//! Input given as text is written to the child's stdin, since it may be too long to pass as an
+//! argument. With a timeout, a child that is still running well after its parts should have
+//! stopped is killed and its parts time out.
+
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::answers::{format_answer, parse_answer, quote, unquote};
+use crate::prelude::*;
+use crate::solutions::check_day;
+
+/// Name of the hidden subcommand that runs a day as a child process
+pub const CHILD_COMMAND: &str = "child";
+
+/// Time a child gets to parse its input and exit, on top of its parts' timeouts
+const KILL_GRACE: Duration = Duration::from_secs(2);
+/// How often to check whether a child with a deadline has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Child process settings for isolated runs
+#[derive(Clone, Debug)]
+pub struct Isolation {
+    /// Binary to run, which must handle the child subcommand
+    pub exe: PathBuf,
+    /// Address space limit in bytes
+    pub max_memory: Option<u64>,
+    /// CPU time limit in seconds
+    pub max_cpu: Option<u64>,
+}
+
+impl Isolation {
+    /// Run children with the current executable and no resource limits
+    pub fn current_exe() -> Result<Self, AOCError> {
+        Ok(Self {
+            exe: std::env::current_exe().map_err(AOCError::Spawn)?,
+            max_memory: None,
+            max_cpu: None,
+        })
+    }
+}
+
+/// Solve a day in a child process. A crash is reported as an error answer for each part, and a
+/// child killed for running past its timeout as a timeout.
+pub fn solve_isolated(
+    day: usize,
+    input: &InputSource,
+    parts: &[Part],
+    options: &RunOptions,
+    isolation: &Isolation,
+) -> Result<Solution, AOCError> {
+    check_day(day, parts, options.algorithm.as_deref())?;
+
+    let mut cmd = Command::new(&isolation.exe);
+    cmd.arg(CHILD_COMMAND).arg(day.to_string());
+    match input {
+        InputSource::File(path) => cmd.arg("--input").arg(path).stdin(Stdio::null()),
+        InputSource::Stdin => cmd.arg("--input").arg("-").stdin(Stdio::inherit()),
+        InputSource::Text(_) => cmd.arg("--input").arg("-").stdin(Stdio::piped()),
+    };
+    if let [part] = parts {
+        cmd.arg("--part").arg(part.to_string());
+    }
+    if options.parse_mode == ParseMode::Strict {
+        cmd.arg("--strict");
+    }
+    if let Some(name) = &options.algorithm {
+        cmd.arg("--algo").arg(name);
+    }
+    if let Some(timeout) = options.timeout {
+        cmd.arg("--timeout").arg(timeout.as_secs_f64().to_string());
+    }
+    if let Some(bytes) = isolation.max_memory {
+        cmd.arg("--memory-bytes").arg(bytes.to_string());
+    }
+    if let Some(secs) = isolation.max_cpu {
+        cmd.arg("--cpu-secs").arg(secs.to_string());
+    }
+
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(AOCError::Spawn)?;
+    // Parts run one after another in the child unless it solves them in parallel
+    let deadline = options
+        .timeout
+        .map(|timeout| Instant::now() + timeout * parts.len() as u32 + KILL_GRACE);
+    let (status, stdout, stderr) = thread::scope(|scope| {
+        if let (InputSource::Text(text), Some(mut stdin)) = (input, child.stdin.take()) {
+            // Write from another thread so a child that fills its output pipes before reading all
+            // of its input can't deadlock. A child that exits early just closes the pipe.
+            scope.spawn(move || stdin.write_all(text.as_bytes()));
+        }
+        let mut stdout = child.stdout.take().unwrap();
+        let mut stderr = child.stderr.take().unwrap();
+        let stdout = scope.spawn(move || read_all(&mut stdout));
+        let stderr = scope.spawn(move || read_all(&mut stderr));
+        let status = wait_until(&mut child, deadline)?;
+        io::Result::Ok((status, stdout.join().unwrap()?, stderr.join().unwrap()?))
+    })
+    .map_err(AOCError::Spawn)?;
+
+    let status = match status {
+        Some(status) => status,
+        None => return Ok(failed_solution(parts, || Answer::Timeout)),
+    };
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        let reason = match crash_message(&stderr) {
+            Some(message) => format!("{}: {}", describe_status(status), message),
+            None => describe_status(status),
+        };
+        return Ok(failed_solution(parts, || {
+            Answer::Error(Box::new(AOCError::Crashed(reason.clone())))
+        }));
+    }
+
+    let result = read_child_output(&String::from_utf8_lossy(&stdout)).map_err(|e| match e {
+        ChildError::Kind(kind, message) => rebuild_error(kind, message, day, parts, options),
+        ChildError::Other(e) => e,
+    });
+    match input {
+        InputSource::File(path) => result.map_err(|e| e.with_path(path)),
+        InputSource::Stdin | InputSource::Text(_) => result,
+    }
+}
+
+// Wait for a child to exit, killing it if it's still running at the deadline. `None` if it was
+// killed.
+fn wait_until(child: &mut Child, deadline: Option<Instant>) -> io::Result<Option<ExitStatus>> {
+    let deadline = match deadline {
+        Some(deadline) => deadline,
+        None => return child.wait().map(Some),
+    };
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn read_all(r: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    r.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+// The same answer for every part, for a child that didn't report any
+fn failed_solution(parts: &[Part], answer: impl Fn() -> Answer) -> Solution {
+    Solution {
+        read_time: Duration::ZERO,
+        parse_time: Duration::ZERO,
+        skipped: 0,
+        parts: parts
+            .iter()
+            .map(|&part| PartSolution {
+                part,
+                answer: answer(),
+                time: Duration::ZERO,
+            })
+            .collect(),
+    }
+}
+
+/// Write a solution, or the error that prevented it, as child output
+pub fn write_child_result(
+    w: &mut impl Write,
+    result: &Result<Solution, AOCError>,
+) -> io::Result<()> {
+    let solution = match result {
+        Ok(solution) => solution,
+        Err(AOCError::ParseError(e)) => {
+            return writeln!(
+                w,
+                "parse_error {} {} {} {} {}",
+                e.line,
+                e.column,
+                quote(&e.text),
+                quote(&e.expected),
+                quote(&e.found)
+            )
+        }
+        Err(e) => {
+            let (kind, message) = match e {
+                AOCError::DayOutOfRange(_) => ("unknown_day", e.to_string()),
+                AOCError::UnknownAlgorithm(..) => ("unknown_algorithm", e.to_string()),
+                AOCError::NoInput => ("no_input", e.to_string()),
+                // The reason, without the message that wraps it
+                AOCError::BadInputFile(reason) => ("bad_input", reason.to_string()),
+                _ => ("other", e.to_string()),
+            };
+            return writeln!(w, "error {} {}", kind, quote(&message));
+        }
+    };
+
+    writeln!(w, "read_ns {}", solution.read_time.as_nanos())?;
+    writeln!(w, "parse_ns {}", solution.parse_time.as_nanos())?;
+    writeln!(w, "skipped {}", solution.skipped)?;
+    for p in &solution.parts {
+        let value = match &p.answer {
+            Answer::Timeout => "timeout".into(),
+            Answer::Signed(n) => format!("signed {}", n),
+            Answer::BigInteger(n) => format!("big {}", n),
+            Answer::Error(e) => format!("error {}", quote(&e.to_string())),
+            answer => format_answer(answer).unwrap_or_default(),
+        };
+        writeln!(w, "part {} {} {}", p.part, p.time.as_nanos(), value)?;
+    }
+    Ok(())
+}
+
+/// Limit the address space and CPU time of the current process
+#[cfg(unix)]
+pub fn apply_limits(max_memory: Option<u64>, max_cpu: Option<u64>) -> Result<(), AOCError> {
+    // The CPU hard limit is a second later, so the child gets SIGXCPU before SIGKILL
+    let limits = [
+        (libc::RLIMIT_AS, max_memory.map(|m| (m, m))),
+        (libc::RLIMIT_CPU, max_cpu.map(|c| (c, c + 1))),
+    ];
+    for (resource, limit) in limits {
+        if let Some((soft, hard)) = limit {
+            let rlimit = libc::rlimit {
+                rlim_cur: soft as libc::rlim_t,
+                rlim_max: hard as libc::rlim_t,
+            };
+            // Safety: setrlimit only reads the struct passed to it
+            if unsafe { libc::setrlimit(resource, &rlimit) } != 0 {
+                return Err(AOCError::Limits(io::Error::last_os_error()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Limit the address space and CPU time of the current process
+#[cfg(not(unix))]
+pub fn apply_limits(max_memory: Option<u64>, max_cpu: Option<u64>) -> Result<(), AOCError> {
+    match (max_memory, max_cpu) {
+        (None, None) => Ok(()),
+        _ => Err(AOCError::Limits(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "resource limits are only supported on unix",
+        ))),
+    }
+}
+
+// Failure reported by a child, or in reading its output
+enum ChildError {
+    // Kind and message of an `error` record
+    Kind(String, String),
+    Other(AOCError),
+}
+
+impl From<AOCError> for ChildError {
+    fn from(e: AOCError) -> Self {
+        Self::Other(e)
+    }
+}
+
+// Rebuild the error a child reported from its kind, using what the parent knows about the run
+fn rebuild_error(
+    kind: String,
+    message: String,
+    day: usize,
+    parts: &[Part],
+    options: &RunOptions,
+) -> AOCError {
+    match kind.as_str() {
+        "unknown_day" => AOCError::DayOutOfRange(day),
+        "unknown_algorithm" => check_day(day, parts, options.algorithm.as_deref())
+            .err()
+            .unwrap_or(AOCError::Child(message)),
+        "no_input" => AOCError::NoInput,
+        "bad_input" => AOCError::BadInputFile(io::Error::other(message)),
+        _ => AOCError::Child(message),
+    }
+}
+
+fn read_child_output(stdout: &str) -> Result<Solution, ChildError> {
+    let mut solution = Solution {
+        read_time: Duration::ZERO,
+        parse_time: Duration::ZERO,
+        skipped: 0,
+        parts: Vec::new(),
+    };
+    for line in stdout.lines() {
+        let bad_record = || AOCError::Child(format!("invalid output {:?}", line));
+        let (key, rest) = line.split_once(' ').ok_or_else(bad_record)?;
+        let nanos = |s: &str| {
+            s.parse()
+                .map(Duration::from_nanos)
+                .map_err(|_| bad_record())
+        };
+        match key {
+            "read_ns" => solution.read_time = nanos(rest)?,
+            "parse_ns" => solution.parse_time = nanos(rest)?,
+            "skipped" => solution.skipped = rest.parse().map_err(|_| bad_record())?,
+            "part" => {
+                let mut split = rest.splitn(3, ' ');
+                let (part, time, value) = match (split.next(), split.next(), split.next()) {
+                    (Some(part), Some(time), Some(value)) => (part, time, value),
+                    _ => return Err(bad_record().into()),
+                };
+                solution.parts.push(PartSolution {
+                    part: part.parse().map_err(|_| bad_record())?,
+                    answer: read_answer(line, value).map_err(|_| bad_record())?,
+                    time: nanos(time)?,
+                });
+            }
+            "parse_error" => {
+                let e = read_parse_error(line, rest).ok_or_else(bad_record)?;
+                return Err(ChildError::Other(e.into()));
+            }
+            "error" => {
+                let (kind, message) = rest.split_once(' ').ok_or_else(bad_record)?;
+                let message = unquote(line, message).map_err(|_| bad_record())?;
+                return Err(ChildError::Kind(kind.into(), message));
+            }
+            _ => return Err(bad_record().into()),
+        }
+    }
+    Ok(solution)
+}
+
+// Fields of a `parse_error` record after its key
+fn read_parse_error(line: &str, fields: &str) -> Option<ParseError> {
+    let (line_number, fields) = fields.split_once(' ')?;
+    let (column, mut fields) = fields.split_once(' ')?;
+    let mut strings = Vec::new();
+    for _ in 0..3 {
+        let (quoted, rest) = split_quoted(fields)?;
+        strings.push(unquote(line, quoted).ok()?);
+        fields = rest.strip_prefix(' ').unwrap_or(rest);
+    }
+    let [text, expected, found] = <[String; 3]>::try_from(strings).ok()?;
+    match fields.is_empty() {
+        true => Some(ParseError {
+            path: None,
+            line: line_number.parse().ok()?,
+            column: column.parse().ok()?,
+            text,
+            expected,
+            found,
+        }),
+        false => None,
+    }
+}
+
+// Split a quoted string, which may contain escaped quotes, off the start of `s`
+fn split_quoted(s: &str) -> Option<(&str, &str)> {
+    let mut chars = s.char_indices();
+    if chars.next()?.1 != '"' {
+        return None;
+    }
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Some(s.split_at(i + 1)),
+            _ => (),
+        }
+    }
+    None
+}
+
+fn read_answer(line: &str, value: &str) -> Result<Answer, ParseError> {
+    if value == "timeout" {
+        return Ok(Answer::Timeout);
+    }
+    if let Some(message) = value.strip_prefix("error ") {
+        let message = unquote(line, message)?;
+        return Ok(Answer::Error(Box::new(AOCError::Child(message))));
+    }
+    if let Some(n) = value.strip_prefix("signed ") {
+        return parse_token(line, n, "an integer").map(Answer::Signed);
+    }
+    if let Some(n) = value.strip_prefix("big ") {
+        return parse_token(line, n, "an integer").map(Answer::BigInteger);
+    }
+    parse_answer(line, value)
+}
+
+fn describe_status(status: ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        if let Some(signal) = status.signal() {
+            let name = match signal {
+                libc::SIGABRT => " (SIGABRT)",
+                libc::SIGBUS => " (SIGBUS)",
+                libc::SIGFPE => " (SIGFPE)",
+                libc::SIGILL => " (SIGILL)",
+                libc::SIGKILL => " (SIGKILL)",
+                libc::SIGSEGV => " (SIGSEGV)",
+                libc::SIGXCPU => " (SIGXCPU)",
+                _ => "",
+            };
+            return format!("killed by signal {}{}", signal, name);
+        }
+    }
+    match status.code() {
+        Some(code) => format!("exited with status {}", code),
+        None => "exited abnormally".into(),
+    }
+}
+
+// The panic message, or else the last line written to stderr
+fn crash_message(stderr: &str) -> Option<&str> {
+    let mut lines = stderr.lines().map(str::trim).filter(|l| !l.is_empty());
+    let panic = lines
+        .clone()
+        .skip_while(|l| !l.contains("panicked at"))
+        .nth(1);
+    panic.or_else(|| lines.next_back())
+}
@@ -1,4 +1,15 @@
+pub mod answers;
+pub mod bench;
+pub mod examples;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod generate;
+pub mod isolate;
+pub mod output;
+pub mod repl;
+pub mod serve;
 pub mod solutions;
+pub mod watch;
 
 pub mod prelude {
     use std::collections::BTreeMap;
@@ -8,24 +19,364 @@ pub mod prelude {
     use std::fs::File;
     use std::io::{self, BufRead};
     use std::num::ParseIntError;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use num_bigint::{BigInt, BigUint};
 
     // Functions
 
     pub fn read_input_lines(
         path: impl AsRef<Path>,
-    ) -> Result<impl Iterator<Item = String>, AOCError> {
+    ) -> Result<InputLines<io::BufReader<File>>, AOCError> {
         let file = File::open(path).map_err(AOCError::BadInputFile)?;
-        let lines = io::BufReader::new(file)
-            .lines()
-            .map(|l| l.map_err(AOCError::BadInputFile))
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter();
-        Ok(lines)
+        Ok(read_lines(io::BufReader::new(file)))
+    }
+
+    pub fn read_lines<R: BufRead>(input: R) -> InputLines<R> {
+        InputLines {
+            lines: input.lines(),
+            error: None,
+            read_time: Duration::ZERO,
+        }
+    }
+
+    /// Parse a token, which should be a slice of `text`, reporting its position on failure
+    pub fn parse_token<T: FromStr>(
+        text: &str,
+        token: &str,
+        expected: &str,
+    ) -> Result<T, ParseError> {
+        token
+            .parse()
+            .map_err(|_| ParseError::token(text, token, expected))
+    }
+
+    /// Rows of digits, `None` for malformed cells
+    pub type DigitGrid = Vec<Vec<Option<u8>>>;
+
+    /// Parse lines of digits in `radix` as a grid as wide as the first line, with the number of
+    /// malformed lines. Lenient mode cuts long lines, leaves short lines short and makes other
+    /// characters `None`. `name` is what a single cell holds, e.g. "height".
+    pub fn parse_digit_grid(
+        lines: &[String],
+        radix: u32,
+        name: &str,
+        mode: ParseMode,
+    ) -> Result<(DigitGrid, usize), ParseError> {
+        let cols = lines.first().map_or(0, |line| line.chars().count());
+        let mut skipped = 0;
+        let rows = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                // A malformed line counts once, however many of its cells are malformed
+                let mut line_skipped = 0;
+                let len = line.chars().count();
+                if len != cols {
+                    mode.skip(&mut line_skipped, || {
+                        ParseError::new(line, len.min(cols), format!("{} {}s", cols, name)).line(i)
+                    })?;
+                }
+                let row = line
+                    .chars()
+                    .take(cols)
+                    .enumerate()
+                    .map(|(j, c)| match c.to_digit(radix) {
+                        Some(d) => Ok(Some(d as u8)),
+                        None => mode
+                            .skip(&mut line_skipped, || {
+                                ParseError::new(line, j, format!("a {}", name)).line(i)
+                            })
+                            .map(|_| None),
+                    })
+                    .collect::<Result<_, _>>()?;
+                skipped += line_skipped.min(1);
+                Ok(row)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((rows, skipped))
+    }
+
+    pub fn run_solutions(
+        day: DayNum,
+        options: &RunOptions,
+    ) -> Result<BTreeMap<usize, Solution>, AOCError> {
+        super::solutions::dispatch(day, options)
+    }
+
+    pub fn run_all(part: Option<Part>, options: &RunOptions) -> BTreeMap<usize, DayOutcome> {
+        super::solutions::solve_all(part, options)
+    }
+
+    // Structs
+
+    #[derive(Clone, Default, Debug)]
+    pub struct RunOptions {
+        /// Run days, and both parts of each day, concurrently on the rayon thread pool
+        pub parallel: bool,
+        /// Whether malformed input is rejected or skipped
+        pub parse_mode: ParseMode,
+        /// Cancels every part that is still running
+        pub cancel: CancelToken,
+        /// Time limit for each part
+        pub timeout: Option<Duration>,
+        /// Called with progress reports from long-running parts
+        pub progress: Option<ProgressFn>,
+        /// Run each day in a child process when solving with `run_solutions`
+        pub isolation: Option<super::isolate::Isolation>,
+        /// Named algorithm to solve parts with instead of the day's default
+        pub algorithm: Option<String>,
+    }
+
+    /// Cooperative cancellation flag, shared between clones and inherited by children
+    #[derive(Clone, Default, Debug)]
+    pub struct CancelToken(Arc<CancelState>);
+
+    #[derive(Default, Debug)]
+    struct CancelState {
+        cancelled: AtomicBool,
+        parent: Option<CancelToken>,
+    }
+
+    impl CancelToken {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Token that is also cancelled when this one is
+        pub fn child(&self) -> Self {
+            Self(Arc::new(CancelState {
+                cancelled: AtomicBool::new(false),
+                parent: Some(self.clone()),
+            }))
+        }
+
+        pub fn cancel(&self) {
+            self.0.cancelled.store(true, Ordering::Relaxed);
+        }
+
+        pub fn is_cancelled(&self) -> bool {
+            self.0.cancelled.load(Ordering::Relaxed)
+                || self
+                    .0
+                    .parent
+                    .as_ref()
+                    .is_some_and(CancelToken::is_cancelled)
+        }
+    }
+
+    /// Steps completed by a long-running part, and the total number of steps if known
+    #[derive(Clone, Copy, Debug)]
+    pub struct Progress {
+        pub day: usize,
+        pub part: Part,
+        pub done: usize,
+        pub total: Option<usize>,
+    }
+
+    /// Progress callback, which may be called from several threads at once
+    #[derive(Clone)]
+    pub struct ProgressFn(pub Arc<dyn Fn(Progress) + Send + Sync>);
+
+    impl fmt::Debug for ProgressFn {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "ProgressFn")
+        }
     }
 
-    pub fn run_solutions(day: DayNum) -> Result<BTreeMap<usize, Vec<Answer>>, AOCError> {
-        super::solutions::dispatch(day)
+    /// Cancellation and progress reporting for a running part. The default context is never
+    /// cancelled and reports progress nowhere.
+    #[derive(Clone, Default, Debug)]
+    pub struct PartContext {
+        pub day: usize,
+        pub part: Option<Part>,
+        pub cancel: CancelToken,
+        pub progress: Option<ProgressFn>,
+    }
+
+    impl PartContext {
+        /// Fail if the part should stop early
+        pub fn check(&self) -> Result<(), Cancelled> {
+            match self.cancel.is_cancelled() {
+                true => Err(Cancelled),
+                false => Ok(()),
+            }
+        }
+
+        /// Report steps completed, out of `total` if known
+        pub fn progress(&self, done: usize, total: Option<usize>) {
+            if let (Some(ProgressFn(f)), Some(part)) = (&self.progress, self.part) {
+                f(Progress {
+                    day: self.day,
+                    part,
+                    done,
+                    total,
+                });
+            }
+        }
+    }
+
+    /// A part stopped early because it was cancelled
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct Cancelled;
+
+    #[derive(Debug)]
+    pub struct Solution {
+        pub read_time: Duration,
+        pub parse_time: Duration,
+        /// Malformed lines skipped while parsing leniently
+        pub skipped: usize,
+        pub parts: Vec<PartSolution>,
+    }
+
+    impl Solution {
+        pub fn total_time(&self) -> Duration {
+            self.read_time + self.parse_time + self.parts.iter().map(|p| p.time).sum::<Duration>()
+        }
+
+        /// Result of a part, if it was solved
+        pub fn part(&self, part: Part) -> Option<&PartSolution> {
+            self.parts.iter().find(|p| p.part == part)
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct PartSolution {
+        pub part: Part,
+        pub answer: Answer,
+        pub time: Duration,
+    }
+
+    /// Results of every algorithm for one part on the same input
+    #[derive(Debug)]
+    pub struct CrossCheck {
+        pub part: Part,
+        pub results: Vec<(&'static str, PartSolution)>,
+    }
+
+    impl CrossCheck {
+        /// Whether every algorithm gave the same answer
+        pub fn agrees(&self) -> bool {
+            self.results
+                .windows(2)
+                .all(|pair| pair[0].1.answer == pair[1].1.answer)
+        }
+    }
+
+    /// Location and description of malformed input
+    #[derive(Debug)]
+    pub struct ParseError {
+        pub path: Option<PathBuf>,
+        /// 1-based line number, 0 if the line is unknown
+        pub line: usize,
+        /// 1-based column in characters
+        pub column: usize,
+        pub text: String,
+        pub expected: String,
+        pub found: String,
+    }
+
+    impl ParseError {
+        /// Error at a 0-based character column of a line of input
+        pub fn new(text: &str, column: usize, expected: impl Into<String>) -> Self {
+            let found = match text.chars().nth(column) {
+                Some(c) => format!("{:?}", c),
+                None => "end of line".into(),
+            };
+            Self {
+                path: None,
+                line: 0,
+                column: column + 1,
+                text: text.into(),
+                expected: expected.into(),
+                found,
+            }
+        }
+
+        /// Error at a token, which should be a slice of `text`
+        pub fn token(text: &str, token: &str, expected: impl Into<String>) -> Self {
+            let offset = (token.as_ptr() as usize)
+                .checked_sub(text.as_ptr() as usize)
+                .filter(|&offset| offset <= text.len() && text.is_char_boundary(offset))
+                .unwrap_or(0);
+            Self {
+                found: format!("{:?}", token),
+                ..Self::new(text, text[..offset].chars().count(), expected)
+            }
+        }
+
+        /// Error for input that ended before `expected`
+        pub fn end_of_input(expected: impl Into<String>) -> Self {
+            Self {
+                found: "end of input".into(),
+                ..Self::new("", 0, expected)
+            }
+        }
+
+        /// Set the 0-based line index of the error
+        pub fn line(self, line: usize) -> Self {
+            Self {
+                line: line + 1,
+                ..self
+            }
+        }
+    }
+
+    impl fmt::Display for ParseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            if let Some(path) = &self.path {
+                write!(f, "{}:", path.display())?;
+            }
+            write!(
+                f,
+                "{}:{}: expected {}, found {}",
+                self.line, self.column, self.expected, self.found
+            )
+        }
+    }
+
+    /// Lazily read lines of input. Iteration stops at the first I/O error, which is returned by
+    /// `finish` once the input has been consumed.
+    pub struct InputLines<R> {
+        lines: io::Lines<R>,
+        error: Option<io::Error>,
+        read_time: Duration,
+    }
+
+    impl<R> InputLines<R> {
+        /// Time spent reading so far, or the I/O error that ended iteration
+        pub fn finish(self) -> Result<Duration, AOCError> {
+            match self.error {
+                Some(e) => Err(AOCError::BadInputFile(e)),
+                None => Ok(self.read_time),
+            }
+        }
+    }
+
+    impl<R: BufRead> Iterator for InputLines<R> {
+        type Item = String;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.error.is_some() {
+                return None;
+            }
+
+            let start = Instant::now();
+            let line = self.lines.next();
+            self.read_time += start.elapsed();
+
+            match line? {
+                Ok(line) => Some(line),
+                Err(e) => {
+                    self.error = Some(e);
+                    None
+                }
+            }
+        }
     }
 
     // Enums
@@ -33,68 +384,402 @@ pub mod prelude {
     #[derive(Debug)]
     pub enum AOCError {
         BadDay(OsString),
+        BadPart(String),
         DayOutOfRange(usize),
         NoInput,
         BadInputFile(io::Error),
-        ParseError,
+        ParseError(Box<ParseError>),
         ParseIntError(ParseIntError, String),
+        WrongAnswers(usize),
+        DaysFailed(usize),
+        BadFormat(String),
+        BadTimeout(String),
+        Output(io::Error),
+        ThreadPool(String),
+        Spawn(io::Error),
+        Limits(io::Error),
+        Child(String),
+        Crashed(String),
+        UnknownAlgorithm(String, Part, &'static [&'static str]),
+        Disagreements(usize),
+        BadHistory(String),
+        BadThreshold(String),
+        NoBaseline(String),
+        Regressions(usize),
+        BadPattern(String, String),
+        NoInputs(String),
+        InputsFailed(usize),
+        UnknownExample(usize, String, Vec<&'static str>),
+        BadCommand(String),
+        UnknownParameter(String, Vec<&'static str>),
+        UnknownView(String, &'static [&'static str]),
+        Listen(io::Error),
+        VerifyFailed(usize, usize, usize),
+        StepLimit(usize),
     }
 
     impl fmt::Display for AOCError {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
             match self {
                 Self::BadDay(s) => write!(f, "Invalid day: {:?}", s),
+                Self::BadPart(s) => write!(f, "Invalid part: {:?}", s),
                 Self::DayOutOfRange(d) => write!(f, "Invalid day: {}", d),
                 Self::NoInput => write!(f, "No input"),
                 Self::BadInputFile(e) => write!(f, "Could not read input file: {}", e),
-                Self::ParseError => write!(f, "Could not parse input"),
+                Self::ParseError(e) => write!(f, "Could not parse input: {}", e),
                 Self::ParseIntError(e, s) => {
                     write!(f, "Could not parse integer from string \"{}\": {}", s, e)
                 }
+                Self::WrongAnswers(n) => write!(f, "{} answer(s) did not match", n),
+                Self::DaysFailed(n) => write!(f, "{} day(s) failed", n),
+                Self::BadFormat(s) => write!(f, "Invalid output format: {:?}", s),
+                Self::BadTimeout(s) => write!(f, "Invalid timeout: {:?}", s),
+                Self::Output(e) => write!(f, "Could not write output: {}", e),
+                Self::ThreadPool(e) => write!(f, "Could not start thread pool: {}", e),
+                Self::Spawn(e) => write!(f, "Could not start child process: {}", e),
+                Self::Limits(e) => write!(f, "Could not set resource limits: {}", e),
+                Self::Child(s) => write!(f, "{}", s),
+                Self::Crashed(s) => write!(f, "Crashed, {}", s),
+                Self::UnknownAlgorithm(name, part, available) => write!(
+                    f,
+                    "No algorithm {:?} for part {}, expected one of: {}",
+                    name,
+                    part,
+                    available.join(", ")
+                ),
+                Self::Disagreements(n) => write!(f, "Algorithms disagreed on {} part(s)", n),
+                Self::BadHistory(s) => write!(f, "Invalid benchmark history: {}", s),
+                Self::BadThreshold(s) => write!(f, "Invalid threshold: {:?}", s),
+                Self::NoBaseline(s) => write!(f, "No benchmark baseline named {:?}", s),
+                Self::Regressions(n) => write!(f, "{} part(s) got slower than the baseline", n),
+                Self::BadPattern(p, e) => write!(f, "Invalid pattern {:?}: {}", p, e),
+                Self::NoInputs(p) => write!(f, "No input files match {:?}", p),
+                Self::InputsFailed(n) => write!(f, "{} input file(s) failed", n),
+                Self::UnknownExample(day, _, names) if names.is_empty() => {
+                    write!(f, "No examples for day {}", day)
+                }
+                Self::UnknownExample(day, name, names) => write!(
+                    f,
+                    "No example {:?} for day {}, expected one of: {}",
+                    name,
+                    day,
+                    names.join(", ")
+                ),
+                Self::BadCommand(s) => write!(f, "Unknown command {}", s),
+                Self::UnknownParameter(name, names) if names.is_empty() => {
+                    write!(f, "No parameter {:?}, this day has none", name)
+                }
+                Self::UnknownParameter(name, names) => write!(
+                    f,
+                    "No parameter {:?}, expected one of: {}",
+                    name,
+                    names.join(", ")
+                ),
+                Self::UnknownView(name, []) => {
+                    write!(f, "No view {:?}, this day has none", name)
+                }
+                Self::UnknownView(name, views) => write!(
+                    f,
+                    "No view {:?}, expected one of: {}",
+                    name,
+                    views.join(", ")
+                ),
+                Self::Listen(e) => write!(f, "Could not listen for connections: {}", e),
+                Self::VerifyFailed(mismatches, unverified, errors) => {
+                    let counts = [
+                        (mismatches, "answer(s) did not match"),
+                        (unverified, "part(s) had no expected answer"),
+                        (errors, "day(s) or part(s) failed"),
+                    ];
+                    let problems: Vec<_> = counts
+                        .iter()
+                        .filter(|(&n, _)| n > 0)
+                        .map(|(n, what)| format!("{} {}", n, what))
+                        .collect();
+                    write!(f, "{}", problems.join(", "))
+                }
+                Self::StepLimit(n) => write!(f, "No answer within {} steps", n),
             }
         }
     }
 
     impl Error for AOCError {}
 
+    impl AOCError {
+        /// Record the input file a parse error came from
+        pub fn with_path(self, path: impl AsRef<Path>) -> Self {
+            match self {
+                Self::ParseError(mut e) => {
+                    e.path = Some(path.as_ref().to_owned());
+                    Self::ParseError(e)
+                }
+                e => e,
+            }
+        }
+    }
+
+    impl From<ParseError> for AOCError {
+        fn from(e: ParseError) -> Self {
+            Self::ParseError(Box::new(e))
+        }
+    }
+
     #[derive(Debug)]
     pub enum Answer {
         Integer(usize),
+        Signed(isize),
+        BigInteger(BigInt),
+        Text(String),
         Printable(Vec<u8>),
         None,
-        Error(Box<dyn Error>),
+        /// The part was cancelled, usually by running past its time limit
+        Timeout,
+        Error(Box<dyn Error + Send + Sync>),
+    }
+
+    impl Answer {
+        pub fn kind(&self) -> &'static str {
+            match self {
+                Self::Integer(_) => "Integer",
+                Self::Signed(_) => "Signed",
+                Self::BigInteger(_) => "BigInteger",
+                Self::Text(_) => "Text",
+                Self::Printable(_) => "Printable",
+                Self::None => "None",
+                Self::Timeout => "Timeout",
+                Self::Error(_) => "Error",
+            }
+        }
+
+        /// Value of an integer answer, whichever variant holds it
+        pub fn to_big_int(&self) -> Option<BigInt> {
+            match self {
+                Self::Integer(n) => Some((*n).into()),
+                Self::Signed(n) => Some((*n).into()),
+                Self::BigInteger(n) => Some(n.clone()),
+                _ => None,
+            }
+        }
     }
 
     impl PartialEq for Answer {
         fn eq(&self, other: &Self) -> bool {
+            // Integers compare by value, so an expected answer matches any integer variant
+            if let (Some(a), Some(b)) = (self.to_big_int(), other.to_big_int()) {
+                return a == b;
+            }
             match self {
-                Self::Integer(a) => matches!(other, Self::Integer(b) if *a == *b),
+                Self::Text(a) => matches!(other, Self::Text(b) if *a == *b),
                 Self::Printable(a) => matches!(other, Self::Printable(b) if *a == *b),
                 Self::None => matches!(other, Self::None),
-                Self::Error(_) => false,
+                Self::Timeout => matches!(other, Self::Timeout),
+                Self::Integer(_) | Self::Signed(_) | Self::BigInteger(_) | Self::Error(_) => false,
+            }
+        }
+    }
+
+    impl From<usize> for Answer {
+        fn from(n: usize) -> Self {
+            Self::Integer(n)
+        }
+    }
+
+    impl From<isize> for Answer {
+        fn from(n: isize) -> Self {
+            Self::Signed(n)
+        }
+    }
+
+    impl From<BigInt> for Answer {
+        fn from(n: BigInt) -> Self {
+            Self::BigInteger(n)
+        }
+    }
+
+    impl From<BigUint> for Answer {
+        fn from(n: BigUint) -> Self {
+            match usize::try_from(&n) {
+                Ok(n) => Self::Integer(n),
+                Err(_) => Self::BigInteger(n.into()),
+            }
+        }
+    }
+
+    impl From<String> for Answer {
+        fn from(s: String) -> Self {
+            Self::Text(s)
+        }
+    }
+
+    impl From<Cancelled> for Answer {
+        fn from(_: Cancelled) -> Self {
+            Self::Timeout
+        }
+    }
+
+    impl<T: Into<Answer>> From<Result<T, Cancelled>> for Answer {
+        fn from(r: Result<T, Cancelled>) -> Self {
+            match r {
+                Ok(x) => x.into(),
+                Err(e) => e.into(),
             }
         }
     }
 
-    impl From<Option<usize>> for Answer {
-        fn from(o: Option<usize>) -> Self {
+    impl<T: Into<Answer>> From<Option<T>> for Answer {
+        fn from(o: Option<T>) -> Self {
             match o {
-                Some(n) => Self::Integer(n),
+                Some(x) => x.into(),
                 None => Self::None,
             }
         }
     }
 
+    /// How parsers treat malformed lines and cells
+    #[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+    pub enum ParseMode {
+        /// Skip or substitute malformed input, counting how much was skipped
+        #[default]
+        Lenient,
+        /// Reject malformed input with a parse error
+        Strict,
+    }
+
+    impl ParseMode {
+        /// Fail with the error in strict mode, otherwise count the malformed input as skipped
+        pub fn skip(
+            self,
+            skipped: &mut usize,
+            error: impl FnOnce() -> ParseError,
+        ) -> Result<(), ParseError> {
+            match self {
+                Self::Strict => Err(error()),
+                Self::Lenient => {
+                    *skipped += 1;
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+    pub enum Part {
+        One,
+        Two,
+    }
+
+    impl Part {
+        pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+        /// 1-based part number
+        pub fn number(self) -> usize {
+            match self {
+                Self::One => 1,
+                Self::Two => 2,
+            }
+        }
+    }
+
+    impl FromStr for Part {
+        type Err = AOCError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "1" => Ok(Self::One),
+                "2" => Ok(Self::Two),
+                _ => Err(AOCError::BadPart(s.into())),
+            }
+        }
+    }
+
+    impl fmt::Display for Part {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.number())
+        }
+    }
+
+    /// What happened to a day when solving all days
+    #[derive(Debug)]
+    pub enum DayOutcome {
+        Solved(Solution),
+        /// The day's input file does not exist
+        NoInput(PathBuf),
+        /// The input could not be read or parsed
+        Failed(AOCError),
+    }
+
+    /// Days to solve, and the part to solve or `None` for both
     pub enum DayNum {
-        One(usize, OsString),
-        All,
+        One(usize, InputSource, Option<Part>),
+        All(Option<Part>),
+    }
+
+    pub enum InputSource {
+        File(OsString),
+        Stdin,
+        Text(String),
     }
 
     // Traits
 
-    pub trait Day {
-        fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> where Self: Sized;
+    pub trait Day: Send + Sync {
+        fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError>
+        where
+            Self: Sized;
+        /// Parse input, days that can skip malformed input check the mode
+        fn new_with_mode(
+            input: impl Iterator<Item = String>,
+            _mode: ParseMode,
+        ) -> Result<Self, AOCError>
+        where
+            Self: Sized,
+        {
+            Self::new(input)
+        }
+        /// Number of malformed lines skipped while parsing
+        fn skipped(&self) -> usize {
+            0
+        }
         fn part_1(&self) -> Answer;
         fn part_2(&self) -> Answer;
+        fn part(&self, part: Part) -> Answer {
+            match part {
+                Part::One => self.part_1(),
+                Part::Two => self.part_2(),
+            }
+        }
+        /// Solve a part, days with long-running loops check `ctx` for cancellation
+        fn run_part(&self, part: Part, _ctx: &PartContext) -> Answer {
+            self.part(part)
+        }
+        /// Names of the algorithms that can solve a part, the first is the one `run_part` uses.
+        /// Days that override this also override `run_algorithm`.
+        fn algorithms(_part: Part) -> &'static [&'static str]
+        where
+            Self: Sized,
+        {
+            &["default"]
+        }
+        /// Solve a part with a named algorithm, `None` if the part has no algorithm by that name
+        fn run_algorithm(&self, part: Part, name: &str, ctx: &PartContext) -> Option<Answer> {
+            (name == "default").then(|| self.run_part(part, ctx))
+        }
+        /// Parameters of the parts, such as step counts, with their current values
+        fn parameters(&self) -> Vec<(&'static str, usize)> {
+            Vec::new()
+        }
+        /// Change a parameter, `false` if the day has no parameter by that name
+        fn set_parameter(&mut self, _name: &str, _value: usize) -> bool {
+            false
+        }
+        /// Names of the intermediate structures that `inspect` can show
+        fn views(&self) -> &'static [&'static str] {
+            &[]
+        }
+        /// Render an intermediate structure after `steps` steps of the day's process, `None` if
+        /// the day has no view by that name
+        fn inspect(&self, _view: &str, _steps: usize) -> Option<String> {
+            None
+        }
     }
 }
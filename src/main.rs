@@ -1,14 +1,57 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
 use std::process::exit;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use aoc2021::answers::*;
+use aoc2021::bench::*;
+use aoc2021::examples::{self, Example};
+use aoc2021::generate::generate;
+use aoc2021::isolate::*;
+use aoc2021::output::*;
 use aoc2021::prelude::*;
-use clap::{App, Arg};
+use aoc2021::repl::Repl;
+use aoc2021::serve::Server;
+use aoc2021::solutions::{self, days};
+use aoc2021::watch::InputWatcher;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 
 fn main() {
-    if let Err(e) = run() {
-        eprintln!("Error: {}", e);
-        exit(1);
+    match run() {
+        Ok(()) => (),
+        Err(AOCError::ParseError(e)) => {
+            eprint!("{}", render_parse_error(&e));
+            exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+// Render a parse error with the offending line and a caret under the column, like a compiler
+// diagnostic
+fn render_parse_error(e: &ParseError) -> String {
+    let mut out = format!("Error: expected {}, found {}\n", e.expected, e.found);
+    let location = format!("{}:{}", e.line, e.column);
+    match &e.path {
+        Some(path) => out += &format!(" --> {}:{}\n", path.display(), location),
+        None => out += &format!(" --> {}\n", location),
+    }
+    if e.line > 0 {
+        let number = e.line.to_string();
+        let gutter = " ".repeat(number.len());
+        out += &format!("{} |\n", gutter);
+        out += &format!("{} | {}\n", number, e.text);
+        out += &format!("{} | {}^\n", gutter, " ".repeat(e.column.saturating_sub(1)));
     }
+    out
 }
 
 fn run() -> Result<(), AOCError> {
@@ -17,44 +60,840 @@ fn run() -> Result<(), AOCError> {
         .author(env!("CARGO_PKG_AUTHORS"))
         .about(env!("CARGO_PKG_DESCRIPTION"))
         .arg(Arg::with_name("day").help("Advent of code day").index(1))
-        .arg(Arg::with_name("input").help("Input file to use").index(2))
+        .arg(
+            Arg::with_name("input")
+                .help("Input file to use, or - to read from stdin")
+                .index(2),
+        )
+        .arg(
+            Arg::with_name("input-text")
+                .long("input-text")
+                .help("Puzzle input to use instead of an input file")
+                .takes_value(true)
+                .value_name("TEXT")
+                .conflicts_with("input")
+                .requires("day"),
+        )
+        .arg(
+            Arg::with_name("example")
+                .long("example")
+                .help("Solve a puzzle example instead of the input, the first if no name is given")
+                .takes_value(true)
+                .min_values(0)
+                .max_values(1)
+                .value_name("NAME")
+                .conflicts_with_all(&["input", "input-text"])
+                .requires("day"),
+        )
+        .arg(
+            Arg::with_name("list")
+                .short("l")
+                .long("list")
+                .help("List implemented days")
+                .conflicts_with_all(&["day", "input", "input-text"]),
+        )
+        .arg(
+            Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .help("Output format")
+                .takes_value(true)
+                .possible_values(FORMATS)
+                .default_value("text"),
+        )
+        .arg(
+            Arg::with_name("time")
+                .short("t")
+                .long("time")
+                .help("Print read, parse and solve times"),
+        )
+        .arg(part_arg().conflicts_with("list"))
+        .arg(timeout_arg())
+        .arg(
+            Arg::with_name("progress")
+                .long("progress")
+                .help("Report the progress of long-running parts on stderr"),
+        )
+        .arg(strict_arg())
+        .arg(
+            Arg::with_name("isolate")
+                .long("isolate")
+                .help("Run each day in a child process, so a crash only fails that day"),
+        )
+        .arg(
+            Arg::with_name("max-memory")
+                .long("max-memory")
+                .help("Memory limit for each child process with --isolate")
+                .takes_value(true)
+                .value_name("MB")
+                .requires("isolate"),
+        )
+        .arg(
+            Arg::with_name("max-cpu")
+                .long("max-cpu")
+                .help("CPU time limit for each child process with --isolate")
+                .takes_value(true)
+                .value_name("SECS")
+                .requires("isolate"),
+        )
+        .arg(
+            Arg::with_name("parallel")
+                .short("p")
+                .long("parallel")
+                .help("Run days and parts in parallel"),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .short("j")
+                .long("jobs")
+                .help("Number of threads to use with --parallel")
+                .takes_value(true)
+                .requires("parallel"),
+        )
+        .arg(algo_arg().requires("day"))
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Check the answers for all inputs against the expected answers")
+                .arg(
+                    Arg::with_name("answers")
+                        .long("answers")
+                        .help("Expected answers file")
+                        .takes_value(true)
+                        .default_value(ANSWERS_PATH),
+                )
+                .arg(
+                    Arg::with_name("update")
+                        .long("update")
+                        .help("Overwrite the expected answers with the current answers"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("crosscheck")
+                .about("Solve a day with every algorithm and flag parts where they disagree")
+                .arg(day_arg("Day to check"))
+                .arg(
+                    Arg::with_name("input")
+                        .help("Input file to use, or - to read from stdin")
+                        .index(2),
+                )
+                .arg(
+                    Arg::with_name("input-text")
+                        .long("input-text")
+                        .help("Puzzle input to use instead of an input file")
+                        .takes_value(true)
+                        .value_name("TEXT")
+                        .conflicts_with("input"),
+                )
+                .arg(part_arg().help("Only check one part"))
+                .arg(strict_arg())
+                .arg(timeout_arg().help("Time limit in seconds for each algorithm")),
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Solve days sent over HTTP, answering with JSON")
+                .arg(
+                    Arg::with_name("port")
+                        .long("port")
+                        .help("Port to listen on, 0 picks a free one")
+                        .takes_value(true)
+                        .default_value("8021"),
+                )
+                .arg(
+                    Arg::with_name("host")
+                        .long("host")
+                        .help("Address to listen on")
+                        .takes_value(true)
+                        .default_value("127.0.0.1"),
+                )
+                .arg(timeout_arg().default_value("10"))
+                .arg(
+                    Arg::with_name("threads")
+                        .long("threads")
+                        .help("Number of requests to handle at once, one per CPU by default")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("repl")
+                .about("Parse a day's input once, then rerun parts and inspect it interactively")
+                .arg(day_arg("Day to start with"))
+                .arg(Arg::with_name("input").help("Input file to use").index(2)),
+        )
+        .subcommand(
+            SubCommand::with_name("watch")
+                .about("Solve a day again whenever its input file changes")
+                .arg(day_arg("Day to solve"))
+                .arg(Arg::with_name("input").help("Input file to watch").index(2))
+                .arg(part_arg())
+                .arg(strict_arg())
+                .arg(timeout_arg())
+                .arg(algo_arg())
+                .arg(
+                    Arg::with_name("interval")
+                        .long("interval")
+                        .help("Seconds between checks of the input file")
+                        .takes_value(true)
+                        .default_value("0.5"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("inputs")
+                .about("Solve a day for every input file in a directory or matching a glob")
+                .arg(day_arg("Day to solve"))
+                .arg(
+                    Arg::with_name("inputs")
+                        .help("Directory of input files, or a glob pattern such as 'inputs/*.txt'")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(part_arg())
+                .arg(strict_arg())
+                .arg(timeout_arg())
+                .arg(algo_arg())
+                .arg(
+                    Arg::with_name("parallel")
+                        .short("p")
+                        .long("parallel")
+                        .help("Solve input files in parallel"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("bench")
+                .about("Time each part, record the times and compare them against a baseline")
+                .arg(
+                    Arg::with_name("day")
+                        .help("Only benchmark this day")
+                        .index(1),
+                )
+                .arg(part_arg().help("Only benchmark this part"))
+                .arg(
+                    Arg::with_name("time")
+                        .long("time")
+                        .help("Seconds to spend measuring each part")
+                        .takes_value(true)
+                        .default_value("1"),
+                )
+                .arg(
+                    Arg::with_name("history")
+                        .long("history")
+                        .help("Benchmark history file")
+                        .takes_value(true)
+                        .default_value(HISTORY_PATH),
+                )
+                .arg(
+                    Arg::with_name("baseline")
+                        .long("baseline")
+                        .help("Compare against the latest run with this name or git revision")
+                        .takes_value(true)
+                        .value_name("NAME"),
+                )
+                .arg(
+                    Arg::with_name("save-as")
+                        .long("save-as")
+                        .help("Name this run so later runs can use it as a baseline")
+                        .takes_value(true)
+                        .value_name("NAME")
+                        .conflicts_with("no-save"),
+                )
+                .arg(
+                    Arg::with_name("no-save")
+                        .long("no-save")
+                        .help("Don't add this run to the history"),
+                )
+                .arg(
+                    Arg::with_name("threshold")
+                        .long("threshold")
+                        .help("Flag parts that got slower than the baseline by more than this")
+                        .takes_value(true)
+                        .value_name("PERCENT")
+                        .default_value("10"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("gen")
+                .about("Print random puzzle input for a day")
+                .arg(day_arg("Day to generate input for"))
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .help("Random seed, the same seed always gives the same input")
+                        .takes_value(true)
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::with_name("size")
+                        .long("size")
+                        .help("Input size, such as the number of lines or the grid width")
+                        .takes_value(true)
+                        .default_value("100"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(CHILD_COMMAND)
+                .about("Solve a day for a parent process running with --isolate")
+                .setting(AppSettings::Hidden)
+                .arg(day_arg("Day to solve"))
+                .arg(
+                    Arg::with_name("input")
+                        .long("input")
+                        .takes_value(true)
+                        .required_unless("input-text"),
+                )
+                .arg(
+                    Arg::with_name("input-text")
+                        .long("input-text")
+                        .takes_value(true),
+                )
+                .arg(part_arg())
+                .arg(strict_arg())
+                .arg(algo_arg())
+                .arg(timeout_arg())
+                .arg(
+                    Arg::with_name("memory-bytes")
+                        .long("memory-bytes")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("cpu-secs")
+                        .long("cpu-secs")
+                        .takes_value(true),
+                ),
+        )
         .get_matches();
 
+    match matches.subcommand() {
+        ("verify", Some(m)) => return verify(m),
+        ("crosscheck", Some(m)) => return crosscheck(m),
+        ("serve", Some(m)) => return serve(m),
+        ("repl", Some(m)) => return repl(m),
+        ("watch", Some(m)) => return watch(m),
+        ("inputs", Some(m)) => return inputs(m),
+        ("bench", Some(m)) => return bench(m),
+        ("gen", Some(m)) => return gen(m),
+        (CHILD_COMMAND, Some(m)) => return child(m),
+        _ => (),
+    }
+
+    if matches.is_present("list") {
+        for day in days() {
+            println!("Day {:2}", day);
+        }
+        return Ok(());
+    }
+
+    let part = matches.value_of("part").map(str::parse).transpose()?;
+    let mut example = None;
     let day = {
         let day_str = matches
             .value_of_os("day")
             .unwrap_or_else(|| OsStr::new("all"));
-        if day_str == "all" {
-            DayNum::All
-        } else {
-            match day_str.to_string_lossy().parse::<usize>() {
-                Ok(d) => {
-                    let input_path = matches
-                        .value_of_os("input")
-                        .map(|s| s.to_owned())
-                        .unwrap_or_else(|| OsString::from(format!("input/day_{:02}.txt", d)));
-                    DayNum::One(d, input_path)
-                }
-                Err(_) => return Err(AOCError::BadDay(day_str.to_owned())),
+        match day_str.to_string_lossy().parse::<usize>() {
+            Ok(d) if matches.is_present("example") => {
+                let e = examples::example(d, matches.value_of("example"))?;
+                example = Some(e);
+                DayNum::One(d, InputSource::Text(e.input.into()), part)
             }
+            Ok(d) => DayNum::One(d, input_source(&matches, d), part),
+            Err(_) if day_str == "all" && !matches.is_present("example") => DayNum::All(part),
+            Err(_) => return Err(AOCError::BadDay(day_str.to_owned())),
         }
     };
 
-    for (day, solution) in run_solutions(day)? {
-        for (part, part_solution) in solution.iter().enumerate() {
-            let solution_text = match part_solution {
-                Answer::Integer(x) => x.to_string(),
-                Answer::None => "No solution".into(),
-                Answer::Printable(x) => match String::from_utf8(x.to_vec()) {
-                    Ok(s) => "\n".to_owned() + &s,
-                    Err(_) => "Invalid UTF8".into(),
-                },
-                Answer::Error(s) => format!("Error: {}", s),
-            };
+    if let Some(jobs) = matches.value_of("jobs") {
+        let num_threads = jobs
+            .parse()
+            .map_err(|e| AOCError::ParseIntError(e, jobs.into()))?;
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build_global()
+            .map_err(|e| AOCError::ThreadPool(e.to_string()))?;
+    }
 
-            println!("Day {:2} Part {}: {}", day, part + 1, solution_text);
+    let isolation = if matches.is_present("isolate") {
+        Some(Isolation {
+            max_memory: parse_u64(&matches, "max-memory")?.map(|mb| mb << 20),
+            max_cpu: parse_u64(&matches, "max-cpu")?,
+            ..Isolation::current_exe()?
+        })
+    } else {
+        None
+    };
+    let options = RunOptions {
+        parallel: matches.is_present("parallel"),
+        progress: matches.is_present("progress").then(progress_reporter),
+        isolation,
+        ..run_options(&matches)?
+    };
+    let format = matches.value_of("format").unwrap().parse()?;
+    let timings = matches.is_present("time");
+    if let DayNum::All(part) = day {
+        return solve_all_days(part, &options, format, timings);
+    }
+    let solutions = run_solutions(day, &options)?;
+    write_solutions(&mut io::stdout().lock(), format, &solutions, timings)
+        .map_err(AOCError::Output)?;
+    match example {
+        Some(example) => check_example(example, &solutions),
+        None => Ok(()),
+    }
+}
+
+// Fail if any part's answer differs from the example's expected answer
+fn check_example(example: &Example, solutions: &BTreeMap<usize, Solution>) -> Result<(), AOCError> {
+    let mut mismatches = 0;
+    for part_solution in solutions.values().flat_map(|solution| &solution.parts) {
+        match example.answer(part_solution.part) {
+            Some(expected) if expected != part_solution.answer => {
+                mismatches += 1;
+                eprintln!(
+                    "Day {:2} Part {}: expected {} for example {}",
+                    example.day,
+                    part_solution.part,
+                    answer_text(&expected),
+                    example.name
+                );
+            }
+            _ => (),
         }
     }
+    match mismatches {
+        0 => Ok(()),
+        n => Err(AOCError::WrongAnswers(n)),
+    }
+}
+
+// Solve every day, then summarize which days were solved, had no input or failed
+fn solve_all_days(
+    part: Option<Part>,
+    options: &RunOptions,
+    format: Format,
+    timings: bool,
+) -> Result<(), AOCError> {
+    let outcomes = run_all(part, options);
+    let rows: Vec<_> = outcomes
+        .iter()
+        .map(|(&day, outcome)| SummaryRow::new(day, outcome))
+        .collect();
+    let solutions: BTreeMap<_, _> = outcomes
+        .into_iter()
+        .filter_map(|(day, outcome)| match outcome {
+            DayOutcome::Solved(solution) => Some((day, solution)),
+            DayOutcome::NoInput(_) | DayOutcome::Failed(_) => None,
+        })
+        .collect();
+
+    let mut stdout = io::stdout().lock();
+    write_solutions(&mut stdout, format, &solutions, timings).map_err(AOCError::Output)?;
+    // Structured output stays parseable with the summary on stderr
+    match format {
+        Format::Text => writeln!(stdout).and_then(|_| write_summary(&mut stdout, &rows)),
+        Format::Json | Format::Csv | Format::Ndjson => write_summary(&mut io::stderr(), &rows),
+    }
+    .map_err(AOCError::Output)?;
+
+    match rows.iter().filter(|row| row.failed).count() {
+        0 => Ok(()),
+        n => Err(AOCError::DaysFailed(n)),
+    }
+}
+
+// Solve one day for a parent process and report the result on stdout
+fn child(matches: &ArgMatches) -> Result<(), AOCError> {
+    apply_limits(
+        parse_u64(matches, "memory-bytes")?,
+        parse_u64(matches, "cpu-secs")?,
+    )?;
+
+    let day = parse_day(matches)?;
+    let part = matches.value_of("part").map(str::parse).transpose()?;
+    let options = run_options(matches)?;
+    let result = run_solutions(DayNum::One(day, input_source(matches, day), part), &options)
+        .and_then(|mut solutions| solutions.remove(&day).ok_or(AOCError::DayOutOfRange(day)));
+    write_child_result(&mut io::stdout().lock(), &result).map_err(AOCError::Output)
+}
+
+// Solve a day with every algorithm, failing if any part's answers disagree
+fn crosscheck(matches: &ArgMatches) -> Result<(), AOCError> {
+    let day = parse_day(matches)?;
+    let parts = parse_parts(matches)?;
+    let options = run_options(matches)?;
+    let checks = solutions::crosscheck(day, &input_source(matches, day), &parts, &options)?;
+    write_crosscheck(&mut io::stdout().lock(), day, &checks).map_err(AOCError::Output)?;
+    match checks.iter().filter(|check| !check.agrees()).count() {
+        0 => Ok(()),
+        n => Err(AOCError::Disagreements(n)),
+    }
+}
+
+// Answer solve requests over HTTP until the listener fails
+fn serve(matches: &ArgMatches) -> Result<(), AOCError> {
+    let port_str = matches.value_of("port").unwrap();
+    let port: u16 = port_str
+        .parse()
+        .map_err(|e| AOCError::ParseIntError(e, port_str.into()))?;
+    // Solve in child processes, since a panic would abort the whole server
+    let options = RunOptions {
+        isolation: Some(Isolation::current_exe()?),
+        ..run_options(matches)?
+    };
+    let mut server = Server::bind((matches.value_of("host").unwrap(), port), options)?;
+    if let Some(threads) = parse_u64(matches, "threads")? {
+        server = server.threads(threads as usize);
+    }
+    eprintln!("Listening on http://{}", server.local_addr()?);
+    server.run()
+}
+
+// Read commands for a day from stdin until it ends or the user quits
+fn repl(matches: &ArgMatches) -> Result<(), AOCError> {
+    let day = parse_day(matches)?;
+    let path = match matches.value_of_os("input") {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(format!("input/day_{:02}.txt", day)),
+    };
+    let mut repl = Repl::new(day, &path)?;
+
+    // Only prompt people, not scripts piping commands in
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        println!(
+            "Loaded day {} from {}, type help for commands",
+            day,
+            path.display()
+        );
+    }
+    repl.run(stdin.lock(), &mut io::stdout().lock(), interactive)
+        .map_err(AOCError::Output)
+}
+
+// Solve a day whenever its input changes, until interrupted
+fn watch(matches: &ArgMatches) -> Result<(), AOCError> {
+    let day = parse_day(matches)?;
+    if !days().any(|d| d == day) {
+        return Err(AOCError::DayOutOfRange(day));
+    }
+    let parts = parse_parts(matches)?;
+    let options = run_options(matches)?;
+    let interval = parse_timeout(matches.value_of("interval").unwrap())?;
+    let path = match matches.value_of_os("input") {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(format!("input/day_{:02}.txt", day)),
+    };
+
+    let mut watcher = InputWatcher::new(&path);
+    let mut previous = None;
+    eprintln!("Watching {}", path.display());
+    loop {
+        if let Some(input) = watcher.poll()? {
+            let result = solutions::solve_parts(day, input, &parts, &options)
+                .map_err(|e| e.with_path(&path));
+            let mut stdout = io::stdout().lock();
+            writeln!(stdout, "\n--- {} ---", path.display())
+                .and_then(|_| write_watch_run(&mut stdout, day, &result, previous.as_ref()))
+                .map_err(AOCError::Output)?;
+            // Keep the last answers that were solved to compare against
+            if let Ok(solution) = result {
+                previous = Some(solution);
+            }
+        }
+        thread::sleep(interval);
+    }
+}
+
+// Solve a day for each input file, failing if any file failed to parse or solve
+fn inputs(matches: &ArgMatches) -> Result<(), AOCError> {
+    let day = parse_day(matches)?;
+    let parts = parse_parts(matches)?;
+    let options = RunOptions {
+        parallel: matches.is_present("parallel"),
+        ..run_options(matches)?
+    };
+    let files = solutions::input_files(matches.value_of("inputs").unwrap())?;
+    let results = solutions::solve_files(day, &files, &parts, &options)?;
+    write_file_table(&mut io::stdout().lock(), &files, &results, &parts)
+        .map_err(AOCError::Output)?;
+
+    let failed = results
+        .iter()
+        .filter(|result| match result {
+            Ok(solution) => solution
+                .parts
+                .iter()
+                .any(|p| matches!(p.answer, Answer::Error(_) | Answer::Timeout)),
+            Err(_) => true,
+        })
+        .count();
+    match failed {
+        0 => Ok(()),
+        n => Err(AOCError::InputsFailed(n)),
+    }
+}
+
+// Time each part, add the run to the history and fail if any part regressed against the baseline
+fn bench(matches: &ArgMatches) -> Result<(), AOCError> {
+    let days: Vec<_> = match matches.is_present("day") {
+        true => {
+            let day = parse_day(matches)?;
+            if !days().any(|d| d == day) {
+                return Err(AOCError::DayOutOfRange(day));
+            }
+            vec![day]
+        }
+        false => days().collect(),
+    };
+    let parts = parse_parts(matches)?;
+    let config = TimerConfig {
+        measure: parse_timeout(matches.value_of("time").unwrap())?,
+        ..TimerConfig::default()
+    };
+    let threshold_str = matches.value_of("threshold").unwrap();
+    let threshold = threshold_str
+        .parse::<f64>()
+        .ok()
+        .filter(|t| t.is_finite())
+        .ok_or_else(|| AOCError::BadThreshold(threshold_str.into()))?;
 
-    Ok(())
+    // Find the baseline first, so a typo fails before spending time benchmarking
+    let history_path = matches.value_of_os("history").unwrap();
+    let mut history = read_history(history_path)?;
+    let baseline = matches
+        .value_of("baseline")
+        .map(|name| {
+            find_baseline(&history, name)
+                .cloned()
+                .ok_or_else(|| AOCError::NoBaseline(name.into()))
+        })
+        .transpose()?;
+
+    let mut results = Vec::new();
+    for day in days {
+        let path = format!("input/day_{:02}.txt", day);
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            // Days without input are skipped unless the day was chosen
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !matches.is_present("day") => continue,
+            Err(e) => return Err(AOCError::BadInputFile(e)),
+        };
+        eprintln!("Benchmarking day {:2}", day);
+        results.extend(bench_day(day, &input, &parts, &config).map_err(|e| e.with_path(&path))?);
+    }
+    let run = BenchRun::new(matches.value_of("save-as").map(str::to_owned), results);
+    let comparisons = compare(&run, baseline.as_ref(), threshold);
+
+    let mut stdout = io::stdout().lock();
+    if let Some(baseline) = &baseline {
+        writeln!(stdout, "Baseline: {}", baseline.rev).map_err(AOCError::Output)?;
+    }
+    write_bench(&mut stdout, &comparisons).map_err(AOCError::Output)?;
+    if !matches.is_present("no-save") {
+        history.push(run);
+        write_history(history_path, &history)?;
+    }
+
+    match comparisons.iter().filter(|c| c.regressed).count() {
+        0 => Ok(()),
+        n => Err(AOCError::Regressions(n)),
+    }
+}
+
+// Print generated input for a day
+fn gen(matches: &ArgMatches) -> Result<(), AOCError> {
+    let day = parse_day(matches)?;
+    let seed = parse_u64(matches, "seed")?.unwrap();
+    let size = parse_u64(matches, "size")?.unwrap() as usize;
+    let input = generate(day, seed, size)?;
+    io::stdout()
+        .lock()
+        .write_all(input.as_bytes())
+        .map_err(AOCError::Output)
+}
+
+// Day number argument of a subcommand
+fn day_arg(help: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name("day").help(help).required(true).index(1)
+}
+
+fn part_arg() -> Arg<'static, 'static> {
+    Arg::with_name("part")
+        .long("part")
+        .help("Only solve this part")
+        .takes_value(true)
+        .possible_values(&["1", "2"])
+}
+
+fn strict_arg() -> Arg<'static, 'static> {
+    Arg::with_name("strict")
+        .short("s")
+        .long("strict")
+        .help("Reject malformed input instead of skipping it")
+}
+
+fn timeout_arg() -> Arg<'static, 'static> {
+    Arg::with_name("timeout")
+        .long("timeout")
+        .help("Stop parts that run longer than this many seconds")
+        .takes_value(true)
+        .value_name("SECS")
+}
+
+fn algo_arg() -> Arg<'static, 'static> {
+    Arg::with_name("algo")
+        .long("algo")
+        .help("Solve with a named algorithm instead of the day's default")
+        .takes_value(true)
+}
+
+fn parse_day(matches: &ArgMatches) -> Result<usize, AOCError> {
+    let day_str = matches.value_of("day").unwrap();
+    day_str
+        .parse()
+        .map_err(|e| AOCError::ParseIntError(e, day_str.into()))
+}
+
+// The chosen part, or both parts
+fn parse_parts(matches: &ArgMatches) -> Result<Vec<Part>, AOCError> {
+    match matches.value_of("part") {
+        Some(part) => Ok(vec![part.parse()?]),
+        None => Ok(Part::ALL.to_vec()),
+    }
+}
+
+// Options from the strict, timeout and algo arguments, where a subcommand has them
+fn run_options(matches: &ArgMatches) -> Result<RunOptions, AOCError> {
+    Ok(RunOptions {
+        parse_mode: parse_mode(matches),
+        timeout: matches.value_of("timeout").map(parse_timeout).transpose()?,
+        algorithm: matches.value_of("algo").map(str::to_owned),
+        ..RunOptions::default()
+    })
+}
+
+fn input_source(matches: &ArgMatches, day: usize) -> InputSource {
+    match (matches.value_of("input-text"), matches.value_of_os("input")) {
+        (Some(text), _) => InputSource::Text(text.into()),
+        (None, Some(path)) if path == "-" => InputSource::Stdin,
+        (None, Some(path)) => InputSource::File(path.to_owned()),
+        (None, None) => InputSource::File(OsString::from(format!("input/day_{:02}.txt", day))),
+    }
+}
+
+fn parse_mode(matches: &ArgMatches) -> ParseMode {
+    if matches.is_present("strict") {
+        ParseMode::Strict
+    } else {
+        ParseMode::Lenient
+    }
+}
+
+fn parse_u64(matches: &ArgMatches, name: &str) -> Result<Option<u64>, AOCError> {
+    matches
+        .value_of(name)
+        .map(|s| s.parse().map_err(|e| AOCError::ParseIntError(e, s.into())))
+        .transpose()
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, AOCError> {
+    s.parse()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| AOCError::BadTimeout(s.into()))
+}
+
+// Print progress reports at most once per interval, so fast loops don't flood stderr
+fn progress_reporter() -> ProgressFn {
+    const INTERVAL: Duration = Duration::from_millis(500);
+
+    let last = Mutex::new(Instant::now());
+    ProgressFn(Arc::new(move |p: Progress| {
+        let mut last = last.lock().unwrap();
+        if last.elapsed() < INTERVAL {
+            return;
+        }
+        *last = Instant::now();
+        match p.total {
+            Some(total) => eprintln!("Day {:2} Part {}: {}/{}", p.day, p.part, p.done, total),
+            None => eprintln!("Day {:2} Part {}: {}", p.day, p.part, p.done),
+        }
+    }))
+}
+
+fn verify(matches: &ArgMatches) -> Result<(), AOCError> {
+    let answers_path = matches.value_of_os("answers").unwrap();
+    let mut solutions = BTreeMap::new();
+    let mut failed = BTreeSet::new();
+    for (day, outcome) in run_all(None, &RunOptions::default()) {
+        match outcome {
+            DayOutcome::Solved(solution) => {
+                solutions.insert(day, solution);
+            }
+            DayOutcome::NoInput(_) => (),
+            DayOutcome::Failed(e) => {
+                println!("Day {:2}: {}", day, e);
+                failed.insert(day);
+            }
+        }
+    }
+
+    if matches.is_present("update") {
+        let answers: Answers = solutions
+            .into_iter()
+            .flat_map(|(day, solution)| {
+                solution
+                    .parts
+                    .into_iter()
+                    .map(move |p| ((day, p.part.number()), p.answer))
+            })
+            .collect();
+        return write_answers(answers_path, &answers);
+    }
+
+    let expected = read_answers(answers_path)?;
+    let mut mismatches = 0;
+    let mut unverified = 0;
+    // Failed days count once each, then parts that failed on days that were solved
+    let mut errors = failed.len();
+    for (&day, solution) in &solutions {
+        let mut day_ok = true;
+        for PartSolution { part, answer, .. } in &solution.parts {
+            match (expected.get(&(day, part.number())), answer) {
+                (Some(e), _) if e == answer => continue,
+                (_, Answer::Error(_) | Answer::Timeout) => {
+                    errors += 1;
+                    println!("Day {:2} Part {}: {}", day, part, answer_text(answer));
+                }
+                (Some(e), _) => {
+                    mismatches += 1;
+                    println!(
+                        "Day {:2} Part {}: expected {}, got {}",
+                        day,
+                        part,
+                        answer_text(e),
+                        answer_text(answer)
+                    );
+                }
+                (None, _) => {
+                    unverified += 1;
+                    println!("Day {:2} Part {}: no expected answer", day, part);
+                }
+            }
+            day_ok = false;
+        }
+        println!("Day {:2}: {}", day, if day_ok { "ok" } else { "not ok" });
+    }
+
+    // Expected answers for days that have no input
+    let missing: BTreeSet<_> = expected
+        .keys()
+        .map(|&(day, _)| day)
+        .filter(|day| !solutions.contains_key(day) && !failed.contains(day))
+        .collect();
+    for day in missing {
+        println!("Day {:2}: no input", day);
+    }
+
+    match (mismatches, unverified, errors) {
+        (0, 0, 0) => Ok(()),
+        _ => Err(AOCError::VerifyFailed(mismatches, unverified, errors)),
+    }
 }
@@ -0,0 +1,437 @@
+//! Rendering of solutions for the CLI, either as text or as structured records.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::bench::Comparison;
+use crate::prelude::*;
+
+pub const FORMATS: &[&str] = &["text", "json", "csv", "ndjson"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Ndjson,
+}
+
+impl FromStr for Format {
+    type Err = AOCError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "ndjson" => Ok(Self::Ndjson),
+            _ => Err(AOCError::BadFormat(s.into())),
+        }
+    }
+}
+
+/// Write solutions in the given format, `timings` adds per-phase timings to text output
+pub fn write_solutions(
+    w: &mut impl Write,
+    format: Format,
+    solutions: &BTreeMap<usize, Solution>,
+    timings: bool,
+) -> io::Result<()> {
+    let parts = solutions.iter().flat_map(|(&day, solution)| {
+        solution
+            .parts
+            .iter()
+            .map(move |part_solution| (day, part_solution.part.number(), solution, part_solution))
+    });
+
+    match format {
+        Format::Text => {
+            for (&day, solution) in solutions {
+                for part_solution in &solution.parts {
+                    writeln!(
+                        w,
+                        "Day {:2} Part {}: {}",
+                        day,
+                        part_solution.part,
+                        answer_text(&part_solution.answer)
+                    )?;
+                }
+                if solution.skipped > 0 {
+                    writeln!(
+                        w,
+                        "Day {:2} Skipped: {} malformed line(s)",
+                        day, solution.skipped
+                    )?;
+                }
+                if timings {
+                    write!(
+                        w,
+                        "Day {:2} Time: read {:?}, parse {:?}",
+                        day, solution.read_time, solution.parse_time
+                    )?;
+                    for part_solution in &solution.parts {
+                        write!(w, ", part {} {:?}", part_solution.part, part_solution.time)?;
+                    }
+                    writeln!(w, ", total {:?}", solution.total_time())?;
+                }
+            }
+            if timings {
+                let total = solutions
+                    .values()
+                    .map(Solution::total_time)
+                    .sum::<Duration>();
+                writeln!(w, "Total Time: {:?}", total)?;
+            }
+        }
+        Format::Json => {
+            let records: Vec<_> = parts
+                .map(|(day, part, solution, part_solution)| {
+                    record(day, part, solution, part_solution)
+                })
+                .collect();
+            serde_json::to_writer_pretty(&mut *w, &records)?;
+            writeln!(w)?;
+        }
+        Format::Ndjson => {
+            for (day, part, solution, part_solution) in parts {
+                serde_json::to_writer(&mut *w, &record(day, part, solution, part_solution))?;
+                writeln!(w)?;
+            }
+        }
+        Format::Csv => {
+            writeln!(w, "day,part,kind,value,time_ns,read_ns,parse_ns,skipped")?;
+            for (day, part, solution, part_solution) in parts {
+                let value = match &part_solution.answer {
+                    Answer::Integer(n) => n.to_string(),
+                    Answer::Signed(n) => n.to_string(),
+                    Answer::BigInteger(n) => n.to_string(),
+                    Answer::Text(t) => csv_quote(t),
+                    Answer::Printable(p) => csv_quote(&String::from_utf8_lossy(p)),
+                    Answer::None | Answer::Timeout => String::new(),
+                    Answer::Error(e) => csv_quote(&e.to_string()),
+                };
+                writeln!(
+                    w,
+                    "{},{},{},{},{},{},{},{}",
+                    day,
+                    part,
+                    part_solution.answer.kind(),
+                    value,
+                    part_solution.time.as_nanos(),
+                    solution.read_time.as_nanos(),
+                    solution.parse_time.as_nanos(),
+                    solution.skipped
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Row of the summary table for an "all" run
+pub struct SummaryRow {
+    pub day: usize,
+    pub status: &'static str,
+    pub detail: String,
+    /// Whether the day counts as a failure for the exit status
+    pub failed: bool,
+}
+
+impl SummaryRow {
+    pub fn new(day: usize, outcome: &DayOutcome) -> Self {
+        let (status, detail, failed) = match outcome {
+            DayOutcome::NoInput(path) => ("no input", path.display().to_string(), false),
+            DayOutcome::Failed(e) => ("failed", e.to_string(), true),
+            DayOutcome::Solved(solution) => {
+                // Parts that crashed or timed out fail an otherwise solved day
+                let error = solution.parts.iter().find_map(|p| match &p.answer {
+                    Answer::Error(e) => Some(("failed", format!("part {}: {}", p.part, e))),
+                    Answer::Timeout => Some(("timed out", format!("part {}", p.part))),
+                    _ => None,
+                });
+                match error {
+                    Some((status, detail)) => (status, detail, true),
+                    None => ("solved", format!("{:?}", solution.total_time()), false),
+                }
+            }
+        };
+        Self {
+            day,
+            status,
+            detail,
+            failed,
+        }
+    }
+}
+
+pub fn write_summary(w: &mut impl Write, rows: &[SummaryRow]) -> io::Result<()> {
+    writeln!(w, "Day  Status     Detail")?;
+    for row in rows {
+        writeln!(w, "{:3}  {:9}  {}", row.day, row.status, row.detail)?;
+    }
+    Ok(())
+}
+
+/// Write every algorithm's answer and time for each part, flagging parts where they disagree
+pub fn write_crosscheck(w: &mut impl Write, day: usize, checks: &[CrossCheck]) -> io::Result<()> {
+    for check in checks {
+        let verdict = match check.agrees() {
+            true => "algorithms agree",
+            false => "algorithms DISAGREE",
+        };
+        writeln!(w, "Day {:2} Part {}: {}", day, check.part, verdict)?;
+        for (name, part_solution) in &check.results {
+            writeln!(
+                w,
+                "  {} ({:?}): {}",
+                name,
+                part_solution.time,
+                answer_text(&part_solution.answer)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a table of part times, with the change since the baseline and regressions flagged
+pub fn write_bench(w: &mut impl Write, comparisons: &[Comparison]) -> io::Result<()> {
+    writeln!(
+        w,
+        "Day  Part      Median        Mean      Stddev  Samples    Baseline   Change"
+    )?;
+    for c in comparisons {
+        write!(
+            w,
+            "{:3}  {:4}  {:>10.2?}  {:>10.2?}  {:>10.2?}  {:7}",
+            c.day, c.part, c.stats.median, c.stats.mean, c.stats.stddev, c.stats.samples
+        )?;
+        match (c.baseline, c.change) {
+            (Some(baseline), Some(change)) => {
+                write!(w, "  {:>10.2?}  {:>+6.1}%", baseline, change * 100.0)?
+            }
+            (Some(baseline), None) => write!(w, "  {:>10.2?}", baseline)?,
+            (None, _) => write!(w, "  {:>10}", "-")?,
+        }
+        if c.regressed {
+            write!(w, "  REGRESSED")?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Write a table of the answers for each input file, flagging files that failed to parse or
+/// solve. Printable answers don't fit in a cell, so they follow the table.
+pub fn write_file_table(
+    w: &mut impl Write,
+    files: &[PathBuf],
+    results: &[Result<Solution, AOCError>],
+    parts: &[Part],
+) -> io::Result<()> {
+    let mut printable = Vec::new();
+    let rows: Vec<_> = files
+        .iter()
+        .zip(results)
+        .map(|(path, result)| {
+            let file = path.display().to_string();
+            let (status, cells) = match result {
+                // The file is already in the first column
+                Err(AOCError::ParseError(e)) => {
+                    let location = format!(
+                        "{}:{}: expected {}, found {}",
+                        e.line, e.column, e.expected, e.found
+                    );
+                    ("parse failed", vec![location])
+                }
+                Err(e) => ("failed", vec![e.to_string()]),
+                Ok(solution) => {
+                    let mut status = "ok";
+                    let mut cells = Vec::new();
+                    for p in &solution.parts {
+                        let cell = match &p.answer {
+                            Answer::Printable(_) => {
+                                printable.push((file.clone(), p));
+                                "see below".into()
+                            }
+                            Answer::Error(e) => {
+                                status = "failed";
+                                format!("Error: {}", e)
+                            }
+                            Answer::Timeout => {
+                                status = "timed out";
+                                answer_text(&p.answer)
+                            }
+                            answer => answer_text(answer),
+                        };
+                        cells.push(cell);
+                    }
+                    cells.push(format!("{:.2?}", solution.total_time()));
+                    (status, cells)
+                }
+            };
+            (file, status, cells)
+        })
+        .collect();
+
+    let mut header = vec!["File".to_owned(), "Status".to_owned()];
+    header.extend(parts.iter().map(|part| format!("Part {}", part)));
+    header.push("Time".into());
+    // Failed files have a single cell, which isn't aligned with the answer columns
+    let mut widths: Vec<_> = header.iter().map(String::len).collect();
+    for (file, status, cells) in &rows {
+        widths[0] = widths[0].max(file.len());
+        widths[1] = widths[1].max(status.len());
+        if cells.len() == parts.len() + 1 {
+            for (width, cell) in widths[2..].iter_mut().zip(cells) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+    }
+
+    let row = |w: &mut dyn Write, cells: &[&str]| {
+        let line: Vec<_> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+            .collect();
+        writeln!(w, "{}", line.join("  ").trim_end())
+    };
+    let header: Vec<_> = header.iter().map(String::as_str).collect();
+    row(w, &header)?;
+    for (file, status, cells) in &rows {
+        let mut line = vec![file.as_str(), status];
+        line.extend(cells.iter().map(String::as_str));
+        row(w, &line)?;
+    }
+
+    for (file, part_solution) in printable {
+        writeln!(
+            w,
+            "\n{} part {}:{}",
+            file,
+            part_solution.part,
+            answer_text(&part_solution.answer)
+        )?;
+    }
+    Ok(())
+}
+
+/// Write the result of re-solving a changed input, with each answer's change since the previous
+/// solution
+pub fn write_watch_run(
+    w: &mut impl Write,
+    day: usize,
+    result: &Result<Solution, AOCError>,
+    previous: Option<&Solution>,
+) -> io::Result<()> {
+    let solution = match result {
+        Ok(solution) => solution,
+        Err(e) => return writeln!(w, "Day {:2}: {}", day, e),
+    };
+    for part_solution in &solution.parts {
+        let answer = &part_solution.answer;
+        let change = match previous.and_then(|p| p.part(part_solution.part)) {
+            None => String::new(),
+            Some(p) if p.answer == *answer => " (unchanged)".into(),
+            // Printable answers span several lines
+            Some(_) if matches!(answer, Answer::Printable(_)) => " (changed)".into(),
+            Some(p) if matches!(p.answer, Answer::Printable(_)) => " (changed)".into(),
+            Some(p) => format!(" (was {})", answer_text(&p.answer)),
+        };
+        writeln!(
+            w,
+            "Day {:2} Part {}: {}{}",
+            day,
+            part_solution.part,
+            answer_text(answer),
+            change
+        )?;
+    }
+    write!(
+        w,
+        "Day {:2} Time: read {:?}, parse {:?}",
+        day, solution.read_time, solution.parse_time
+    )?;
+    for part_solution in &solution.parts {
+        write!(w, ", part {} {:?}", part_solution.part, part_solution.time)?;
+    }
+    writeln!(w, ", total {:?}", solution.total_time())
+}
+
+/// Human readable form of an answer, printable answers start on a new line
+pub fn answer_text(answer: &Answer) -> String {
+    match answer {
+        Answer::Integer(x) => x.to_string(),
+        Answer::Signed(x) => x.to_string(),
+        Answer::BigInteger(x) => x.to_string(),
+        Answer::Text(x) => x.clone(),
+        Answer::None => "No solution".into(),
+        Answer::Timeout => "Timed out".into(),
+        Answer::Printable(x) => match String::from_utf8(x.to_vec()) {
+            Ok(s) => "\n".to_owned() + &s,
+            Err(_) => "Invalid UTF8".into(),
+        },
+        Answer::Error(s) => format!("Error: {}", s),
+    }
+}
+
+/// JSON value of an answer, big integers are strings to keep their precision and printable
+/// answers are split into an array of rows
+pub fn answer_value(answer: &Answer) -> Value {
+    match answer {
+        Answer::Integer(n) => json!(n),
+        Answer::Signed(n) => json!(n),
+        Answer::BigInteger(n) => json!(n.to_string()),
+        Answer::Text(t) => json!(t),
+        Answer::Printable(p) => json!(String::from_utf8_lossy(p).lines().collect::<Vec<_>>()),
+        Answer::None | Answer::Timeout => Value::Null,
+        Answer::Error(e) => json!(e.to_string()),
+    }
+}
+
+/// JSON object with a day's answers and timings
+pub fn solution_value(day: usize, solution: &Solution) -> Value {
+    let parts: Vec<_> = solution
+        .parts
+        .iter()
+        .map(|part_solution| {
+            json!({
+                "part": part_solution.part.number(),
+                "kind": part_solution.answer.kind(),
+                "value": answer_value(&part_solution.answer),
+                "time_ns": part_solution.time.as_nanos() as u64,
+            })
+        })
+        .collect();
+    json!({
+        "day": day,
+        "read_ns": solution.read_time.as_nanos() as u64,
+        "parse_ns": solution.parse_time.as_nanos() as u64,
+        "total_ns": solution.total_time().as_nanos() as u64,
+        "skipped": solution.skipped,
+        "parts": parts,
+    })
+}
+
+fn record(day: usize, part: usize, solution: &Solution, part_solution: &PartSolution) -> Value {
+    json!({
+        "day": day,
+        "part": part,
+        "kind": part_solution.answer.kind(),
+        "value": answer_value(&part_solution.answer),
+        "time_ns": part_solution.time.as_nanos() as u64,
+        "read_ns": solution.read_time.as_nanos() as u64,
+        "parse_ns": solution.parse_time.as_nanos() as u64,
+        "skipped": solution.skipped,
+    })
+}
+
+fn csv_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
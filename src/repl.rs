@@ -0,0 +1,186 @@
+//! Interactive shell for exploring a day's parsed input.
+//!
+//! The input is parsed once, then parts can be rerun, parameters changed and intermediate
+//! structures shown without parsing again.
+
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::output::answer_text;
+use crate::prelude::*;
+use crate::solutions::get_runner;
+
+pub const HELP: &str = "\
+Commands:
+  1, 2, part N       Solve a part
+  run                Solve both parts
+  reload             Parse the input file again, keeping parameters
+  load PATH          Parse another input file for the current day
+  day N [PATH]       Switch to another day
+  params             List the day's parameters
+  set NAME VALUE     Change a parameter
+  show [VIEW] [N]    Show an intermediate structure after N steps, or list the views
+  help               Show this help
+  quit, exit         Leave the shell";
+
+/// Whether the shell should keep reading commands
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+pub struct Repl {
+    day: usize,
+    path: PathBuf,
+    runner: Box<dyn Day>,
+    /// Parameters changed with `set`, applied again when the input is reloaded
+    parameters: Vec<(String, usize)>,
+}
+
+impl Repl {
+    /// Shell for a day, with its input parsed from a file
+    pub fn new(day: usize, path: impl AsRef<Path>) -> Result<Self, AOCError> {
+        Ok(Self {
+            day,
+            path: path.as_ref().to_owned(),
+            runner: get_runner(day, &path)?,
+            parameters: Vec::new(),
+        })
+    }
+
+    pub fn day(&self) -> usize {
+        self.day
+    }
+
+    /// Run commands until the input ends or a quit command, printing a prompt before each one
+    /// if `prompt` is set. Failed commands print their error and the shell carries on.
+    pub fn run(&mut self, input: impl BufRead, w: &mut impl Write, prompt: bool) -> io::Result<()> {
+        let mut lines = input.lines();
+        loop {
+            if prompt {
+                write!(w, "day {:02}> ", self.day)?;
+                w.flush()?;
+            }
+            let line = match lines.next() {
+                Some(line) => line?,
+                None => break,
+            };
+            match self.execute(&line, w) {
+                Ok(Flow::Continue) => (),
+                Ok(Flow::Quit) => break,
+                Err(AOCError::Output(e)) => return Err(e),
+                Err(e) => writeln!(w, "Error: {}", e)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Run one command
+    pub fn execute(&mut self, line: &str, w: &mut impl Write) -> Result<Flow, AOCError> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let result = match words.as_slice() {
+            [] => Ok(()),
+            ["quit" | "exit"] => return Ok(Flow::Quit),
+            ["help"] => writeln!(w, "{}", HELP),
+            [part] | ["part", part] if part.parse::<Part>().is_ok() => {
+                return self.solve(&[part.parse()?], w).map(|_| Flow::Continue)
+            }
+            ["run"] => return self.solve(&Part::ALL, w).map(|_| Flow::Continue),
+            ["reload"] => {
+                let runner = self.load(&self.path)?;
+                self.runner = runner;
+                writeln!(w, "Reloaded {}", self.path.display())
+            }
+            ["load", path] => {
+                self.runner = self.load(Path::new(path))?;
+                self.path = PathBuf::from(path);
+                writeln!(w, "Loaded {}", self.path.display())
+            }
+            ["day", day] | ["day", day, _] => {
+                let day = parse_number(day)?;
+                let path = match words.get(2) {
+                    Some(path) => PathBuf::from(path),
+                    None => PathBuf::from(format!("input/day_{:02}.txt", day)),
+                };
+                *self = Self::new(day, &path)?;
+                writeln!(w, "Loaded day {} from {}", day, path.display())
+            }
+            ["params"] => {
+                let parameters = self.runner.parameters();
+                if parameters.is_empty() {
+                    writeln!(w, "Day {} has no parameters", self.day)
+                } else {
+                    parameters
+                        .iter()
+                        .try_for_each(|(name, value)| writeln!(w, "{} = {}", name, value))
+                }
+            }
+            ["set", name, value] => {
+                let value = parse_number(value)?;
+                self.set_parameter(name, value)?;
+                self.parameters.retain(|(n, _)| n != name);
+                self.parameters.push((name.to_string(), value));
+                writeln!(w, "{} = {}", name, value)
+            }
+            ["show"] => {
+                let views = self.runner.views();
+                if views.is_empty() {
+                    writeln!(w, "Day {} has no views", self.day)
+                } else {
+                    writeln!(w, "Views: {}", views.join(", "))
+                }
+            }
+            ["show", view] | ["show", view, _] => {
+                let steps = words.get(2).map(|s| parse_number(s)).transpose()?;
+                let text = self
+                    .runner
+                    .inspect(view, steps.unwrap_or(0))
+                    .ok_or_else(|| AOCError::UnknownView(view.to_string(), self.runner.views()))?;
+                write!(w, "{}", text).and_then(|_| match text.ends_with('\n') {
+                    true => Ok(()),
+                    false => writeln!(w),
+                })
+            }
+            [command, ..] => return Err(AOCError::BadCommand(format!("{:?}, try help", command))),
+        };
+        result.map_err(AOCError::Output)?;
+        Ok(Flow::Continue)
+    }
+
+    fn solve(&self, parts: &[Part], w: &mut impl Write) -> Result<(), AOCError> {
+        let ctx = PartContext::default();
+        for &part in parts {
+            let start = Instant::now();
+            let answer = self.runner.run_part(part, &ctx);
+            let time = start.elapsed();
+            writeln!(w, "Part {}: {} ({:?})", part, answer_text(&answer), time)
+                .map_err(AOCError::Output)?;
+        }
+        Ok(())
+    }
+
+    // Parse an input file for the current day, with the parameters set so far
+    fn load(&self, path: &Path) -> Result<Box<dyn Day>, AOCError> {
+        let mut runner = get_runner(self.day, path)?;
+        for (name, value) in &self.parameters {
+            runner.set_parameter(name, *value);
+        }
+        Ok(runner)
+    }
+
+    fn set_parameter(&mut self, name: &str, value: usize) -> Result<(), AOCError> {
+        match self.runner.set_parameter(name, value) {
+            true => Ok(()),
+            false => {
+                let names = self.runner.parameters().iter().map(|(n, _)| *n).collect();
+                Err(AOCError::UnknownParameter(name.into(), names))
+            }
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Result<usize, AOCError> {
+    s.parse().map_err(|e| AOCError::ParseIntError(e, s.into()))
+}
@@ -0,0 +1,397 @@
+//! Small HTTP/1.1 server for solving days from other tools.
+//!
+//! `POST /solve?day=N` solves a day with the request body as its input and returns the answers
+//! and timings as JSON. The query may also have `part=P` to solve only one part, `strict=1` to
+//! reject malformed input and `algo=NAME` to choose an algorithm. `GET /days` lists the days
+//! that can be solved. Failures return an error status with a body such as
+//! `{"error": {"kind": "parse_error", "message": "...", "line": 2, "column": 10, ...}}`.
+//!
+//! Query names and values are percent-decoded.
+//!
+//! A fixed number of threads handle connections, each closed after one response, so further
+//! connections wait until a thread is free. Days are solved in a child process when the options
+//! have an isolation, otherwise a panic while solving is reported as an error, though that can't
+//! be caught in builds that abort on panic.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::isolate::solve_isolated;
+use crate::output::solution_value;
+use crate::prelude::*;
+use crate::solutions::{days, solve_parts};
+
+/// Largest request line and headers accepted, in bytes
+const MAX_HEAD: usize = 16 * 1024;
+/// Largest request body accepted, in bytes
+const MAX_BODY: usize = 64 * 1024 * 1024;
+/// How long to wait for a client to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long to wait after failing to accept a connection, so running out of file descriptors
+/// doesn't spin
+const ACCEPT_RETRY: Duration = Duration::from_millis(100);
+
+pub struct Server {
+    listener: TcpListener,
+    options: RunOptions,
+    threads: usize,
+}
+
+impl Server {
+    /// Listen on an address, solving requests with `options` on one thread per CPU. Use port 0
+    /// to pick a free port.
+    pub fn bind(addr: impl ToSocketAddrs, options: RunOptions) -> Result<Self, AOCError> {
+        let listener = TcpListener::bind(addr).map_err(AOCError::Listen)?;
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+        Ok(Self {
+            listener,
+            options,
+            threads,
+        })
+    }
+
+    /// Handle at most this many connections at once
+    pub fn threads(self, threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            ..self
+        }
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, AOCError> {
+        self.listener.local_addr().map_err(AOCError::Listen)
+    }
+
+    /// Handle connections until the listener stops working
+    pub fn run(&self) -> Result<(), AOCError> {
+        thread::scope(|scope| {
+            let threads: Vec<_> = (0..self.threads)
+                .map(|_| scope.spawn(|| self.accept()))
+                .collect();
+            threads
+                .into_iter()
+                .try_for_each(|thread| thread.join().unwrap())
+        })
+    }
+
+    // Handle connections one at a time until the listener stops working
+    fn accept(&self) -> Result<(), AOCError> {
+        for stream in self.listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    // The client has gone away if the response can't be written
+                    let _ = handle(stream, &self.options);
+                }
+                Err(e) if ends_listener(&e) => return Err(AOCError::Listen(e)),
+                Err(e) => {
+                    eprintln!("Could not accept a connection: {}", e);
+                    thread::sleep(ACCEPT_RETRY);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Whether an accept error means the listener itself is broken, rather than that one connection
+// failed or the process is short of resources for now
+fn ends_listener(e: &io::Error) -> bool {
+    #[cfg(unix)]
+    if let Some(code) = e.raw_os_error() {
+        return matches!(
+            code,
+            libc::EBADF | libc::EINVAL | libc::ENOTSOCK | libc::EOPNOTSUPP
+        );
+    }
+    e.kind() == io::ErrorKind::InvalidInput
+}
+
+// Read one request from a connection and write its response
+fn handle(stream: TcpStream, options: &RunOptions) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let (status, body) = match read_request(&mut reader) {
+        Ok(request) => respond(&request, options),
+        Err(e) => (e.status, e.to_value()),
+    };
+    let body = body.to_string();
+    let mut w = io::BufWriter::new(stream);
+    write!(
+        w,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    )?;
+    w.flush()
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// Failure to handle a request, with the HTTP status to report it with
+struct HttpError {
+    status: u16,
+    kind: &'static str,
+    message: String,
+    /// Extra fields for the error object, such as the position of a parse error
+    details: Value,
+}
+
+impl HttpError {
+    fn new(status: u16, kind: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            kind,
+            message: message.into(),
+            details: json!({}),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        let mut error = json!({"kind": self.kind, "message": self.message});
+        if let (Value::Object(error), Value::Object(details)) = (&mut error, &self.details) {
+            error.extend(details.clone());
+        }
+        json!({ "error": error })
+    }
+}
+
+impl From<AOCError> for HttpError {
+    fn from(e: AOCError) -> Self {
+        let message = e.to_string();
+        match e {
+            AOCError::DayOutOfRange(_) => Self::new(404, "unknown_day", message),
+            AOCError::BadPart(_) | AOCError::ParseIntError(..) => {
+                Self::new(400, "bad_request", message)
+            }
+            AOCError::UnknownAlgorithm(..) => Self::new(400, "unknown_algorithm", message),
+            AOCError::BadInputFile(_) => Self::new(400, "bad_input", message),
+            AOCError::NoInput => Self::new(422, "no_input", message),
+            AOCError::Crashed(_) => Self::new(500, "crashed", message),
+            AOCError::ParseError(e) => Self {
+                details: json!({
+                    "line": e.line,
+                    "column": e.column,
+                    "expected": e.expected,
+                    "found": e.found,
+                }),
+                ..Self::new(422, "parse_error", message)
+            },
+            _ => Self::new(500, "internal", message),
+        }
+    }
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request, HttpError> {
+    let bad_request = |message: &str| HttpError::new(400, "bad_request", message);
+    let mut head = reader.take(MAX_HEAD as u64);
+    let mut read_line = || {
+        let mut line = String::new();
+        match head.read_line(&mut line) {
+            Ok(_) if !line.ends_with('\n') => {
+                Err(bad_request("Request head is incomplete or too large"))
+            }
+            Ok(_) => Ok(line.trim_end().to_owned()),
+            Err(e) => Err(bad_request(&format!("Could not read request: {}", e))),
+        }
+    };
+
+    let request_line = read_line()?;
+    let mut words = request_line.split(' ');
+    let (method, target) = match (words.next(), words.next(), words.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_owned(), target.to_owned())
+        }
+        _ => return Err(bad_request("Invalid request line")),
+    };
+
+    let mut length = 0;
+    loop {
+        let line = read_line()?;
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| bad_request("Invalid header"))?;
+        if name.eq_ignore_ascii_case("content-length") {
+            let n = value
+                .trim()
+                .parse()
+                .map_err(|_| bad_request("Invalid Content-Length"))?;
+            length = n;
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err(HttpError::new(
+                411,
+                "length_required",
+                "Chunked bodies aren't supported, send a Content-Length",
+            ));
+        }
+    }
+
+    if length > MAX_BODY {
+        return Err(HttpError::new(
+            413,
+            "too_large",
+            format!("Input is larger than {} bytes", MAX_BODY),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| bad_request(&format!("Could not read request body: {}", e)))?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Ok((percent_decode(name)?, percent_decode(value)?))
+        })
+        .collect::<Result<_, HttpError>>()?;
+    Ok(Request {
+        method,
+        path: path.to_owned(),
+        query,
+        body,
+    })
+}
+
+// Status and body of the response to a request
+fn respond(request: &Request, options: &RunOptions) -> (u16, Value) {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/solve") => solve(request, options),
+        ("GET", "/days") => Ok(json!({ "days": days().collect::<Vec<_>>() })),
+        (_, "/solve" | "/days") => Err(HttpError::new(
+            405,
+            "method_not_allowed",
+            format!("{} is not allowed for {}", request.method, request.path),
+        )),
+        (_, path) => Err(HttpError::new(
+            404,
+            "not_found",
+            format!("No endpoint {}", path),
+        )),
+    };
+    match result {
+        Ok(value) => (200, value),
+        Err(e) => (e.status, e.to_value()),
+    }
+}
+
+fn solve(request: &Request, options: &RunOptions) -> Result<Value, HttpError> {
+    if let Some((name, _)) = request
+        .query
+        .iter()
+        .find(|(name, _)| !["day", "part", "strict", "algo"].contains(&name.as_str()))
+    {
+        return Err(HttpError::new(
+            400,
+            "bad_request",
+            format!("Unknown query parameter {:?}", name),
+        ));
+    }
+    let day_str = request
+        .param("day")
+        .ok_or_else(|| HttpError::new(400, "bad_request", "Missing query parameter \"day\""))?;
+    let day = day_str
+        .parse()
+        .map_err(|e| AOCError::ParseIntError(e, day_str.into()))?;
+    let parts = match request.param("part") {
+        Some(part) => vec![part.parse()?],
+        None => Part::ALL.to_vec(),
+    };
+    let options = RunOptions {
+        parse_mode: match request.param("strict") {
+            Some("" | "1" | "true") => ParseMode::Strict,
+            _ => ParseMode::default(),
+        },
+        algorithm: request.param("algo").map(str::to_owned),
+        ..options.clone()
+    };
+
+    let solution = match &options.isolation {
+        Some(isolation) => {
+            let input = String::from_utf8(request.body.clone())
+                .map_err(|_| HttpError::new(400, "bad_input", "Input is not valid UTF-8"))?;
+            solve_isolated(day, &InputSource::Text(input), &parts, &options, isolation)?
+        }
+        None => panic::catch_unwind(AssertUnwindSafe(|| {
+            solve_parts(day, request.body.as_slice(), &parts, &options)
+        }))
+        .unwrap_or_else(|payload| Err(AOCError::Crashed(panic_message(payload.as_ref()))))?,
+    };
+    Ok(solution_value(day, &solution))
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => format!("panicked: {}", message),
+        (None, Some(message)) => format!("panicked: {}", message),
+        (None, None) => "panicked".into(),
+    }
+}
+
+// Decode `%XX` escapes, and `+` as a space as in form data
+fn percent_decode(s: &str) -> Result<String, HttpError> {
+    let bad_escape = || HttpError::new(400, "bad_request", format!("Invalid escape in {:?}", s));
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                // Both digits are checked, since `from_str_radix` would accept a sign
+                let hex = rest.get(..2).ok_or_else(bad_escape)?;
+                if !hex.iter().all(u8::is_ascii_hexdigit) {
+                    return Err(bad_escape());
+                }
+                let hex = std::str::from_utf8(hex).unwrap();
+                bytes.push(u8::from_str_radix(hex, 16).unwrap());
+                rest = &rest[2..];
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| bad_escape())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        _ => "Internal Server Error",
+    }
+}
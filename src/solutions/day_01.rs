@@ -1,60 +1,72 @@
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
-use itertools::Itertools;
 
 pub struct Day01 {
-    input: Vec<usize>,
+    depths: Vec<usize>,
+    /// Depths summed in each window for part 2
+    window: usize,
 }
 
 impl Day for Day01 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let parsed = input
-            .map(|s| s.parse().map_err(|e| AOCError::ParseIntError(e, s)))
+        let depths = input
+            .enumerate()
+            .map(|(i, s)| parse_token(&s, &s, "a depth").map_err(|e| e.line(i)))
             .collect::<Result<_, _>>()?;
-        Ok(Day01 { input: parsed })
+        Ok(Day01 { depths, window: 3 })
     }
 
     fn part_1(&self) -> Answer {
-        Answer::Integer(
-            self.input
-                .iter()
-                .tuple_windows::<(_, _)>()
-                .filter_map(|(a, b)| if b > a { Some(()) } else { None })
-                .count(),
-        )
+        Answer::Integer(window_increases(&self.depths, 1))
     }
 
     fn part_2(&self) -> Answer {
-        Answer::Integer(
-            self.input
-                .iter()
-                .tuple_windows::<(_, _, _)>()
-                .tuple_windows::<(_, _)>()
-                .filter_map(|((a, b, c), (x, y, z))| {
-                    if x + y + z > a + b + c {
-                        Some(())
-                    } else {
-                        None
-                    }
-                })
-                .count(),
-        )
+        Answer::Integer(window_increases(&self.depths, self.window))
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, usize)> {
+        vec![("window", self.window)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: usize) -> bool {
+        match name {
+            "window" => self.window = value,
+            _ => return false,
+        }
+        true
     }
 }
 
+impl Generate for Day01 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        // Depths drift deeper with some noise, like a sonar sweep
+        let mut depth = rng.range(100..200);
+        (0..size)
+            .map(|_| {
+                depth = (depth + rng.range(0..30)).saturating_sub(10);
+                format!("{}\n", depth)
+            })
+            .collect()
+    }
+}
+
+// Count the times the sum of a sliding window of depths increases. Consecutive windows share all
+// but their outer depths, so only those are compared.
+fn window_increases(depths: &[usize], window: usize) -> usize {
+    depths
+        .iter()
+        .zip(depths.iter().skip(window))
+        .filter(|(first, last)| last > first)
+        .count()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::{prop_assert_eq, proptest};
 
-    static INPUT: &str = "199
-200
-208
-210
-200
-207
-240
-269
-260
-263";
+    static INPUT: &str = include_str!("../../fixtures/day_01/1.txt");
 
     #[test]
     fn part_1() {
@@ -67,4 +79,16 @@ mod test {
         let runner = Day01::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(5));
     }
+
+    proptest! {
+        #[test]
+        fn window_counts(depths in vec(0..10_000usize, 0..200)) {
+            let runner = Day01::new(depths.iter().map(|d| d.to_string())).unwrap();
+            let increases = depths.windows(2).filter(|w| w[1] > w[0]).count();
+            let sums: Vec<usize> = depths.windows(3).map(|w| w.iter().sum()).collect();
+            let window_increases = sums.windows(2).filter(|w| w[1] > w[0]).count();
+            prop_assert_eq!(runner.part_1(), Answer::Integer(increases));
+            prop_assert_eq!(runner.part_2(), Answer::Integer(window_increases));
+        }
+    }
 }
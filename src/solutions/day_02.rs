@@ -1,66 +1,51 @@
+use std::str::FromStr;
+
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
-use itertools::Itertools;
 
 pub struct Day02 {
-    input: Vec<Movement>,
+    movements: Vec<Movement>,
 }
 
 impl Day for Day02 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let parsed = input
-            .map(|s| {
-                let split: (_, _) = s.split(' ').next_tuple().ok_or(AOCError::ParseError)?;
-                match (split.0.to_lowercase().as_str(), split.1.parse()) {
-                    ("forward", Ok(n)) => Ok(Movement::Forward(n)),
-                    ("down", Ok(n)) => Ok(Movement::Down(n)),
-                    ("up", Ok(n)) => Ok(Movement::Up(n)),
-                    (s, Err(e)) => Err(AOCError::ParseIntError(e, s.into())),
-                    _ => Err(AOCError::ParseError),
-                }
-            })
+        let movements = input
+            .enumerate()
+            .map(|(i, s)| s.parse().map_err(|e: ParseError| e.line(i)))
             .collect::<Result<_, _>>()?;
-        Ok(Day02 { input: parsed })
+        Ok(Day02 { movements })
     }
 
     fn part_1(&self) -> Answer {
-        let final_pos = self
-            .input
-            .iter()
-            .fold(Default::default(), |mut acc: Position, m| {
-                match m {
-                    Movement::Forward(n) => acc.horizontal += n,
-                    Movement::Down(n) => acc.depth += n,
-                    Movement::Up(n) => acc.depth -= n,
-                }
-                acc
-            });
-
-        (final_pos.horizontal * final_pos.depth)
-            .try_into()
-            .ok()
-            .into()
+        let position = self.course(Position::apply);
+        Answer::Signed(position.horizontal * position.depth)
     }
 
     fn part_2(&self) -> Answer {
-        let final_pos = self
-            .input
-            .iter()
-            .fold(Default::default(), |mut acc: Position, m| {
-                match m {
-                    Movement::Forward(n) => {
-                        acc.horizontal += n;
-                        acc.depth += acc.aim * n;
-                    }
-                    Movement::Down(n) => acc.aim += n,
-                    Movement::Up(n) => acc.aim -= n,
-                }
-                acc
-            });
-
-        (final_pos.horizontal * final_pos.depth)
-            .try_into()
-            .ok()
-            .into()
+        let position = self.course(Position::apply_with_aim);
+        Answer::Signed(position.horizontal * position.depth)
+    }
+}
+
+impl Day02 {
+    // Follow the movements from the start, applying each with `apply`
+    fn course(&self, apply: impl Fn(&mut Position, &Movement)) -> Position {
+        let mut position = Position::default();
+        for movement in &self.movements {
+            apply(&mut position, movement);
+        }
+        position
+    }
+}
+
+impl Generate for Day02 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let direction = rng.choose(&["forward", "down", "up"]);
+                format!("{} {}\n", direction, rng.range(1..10))
+            })
+            .collect()
     }
 }
 
@@ -71,22 +56,56 @@ struct Position {
     aim: isize,
 }
 
+impl Position {
+    fn apply(&mut self, m: &Movement) {
+        match m {
+            Movement::Forward(n) => self.horizontal += n,
+            Movement::Down(n) => self.depth += n,
+            Movement::Up(n) => self.depth -= n,
+        }
+    }
+
+    fn apply_with_aim(&mut self, m: &Movement) {
+        match m {
+            Movement::Forward(n) => {
+                self.horizontal += n;
+                self.depth += self.aim * n;
+            }
+            Movement::Down(n) => self.aim += n,
+            Movement::Up(n) => self.aim -= n,
+        }
+    }
+}
+
 enum Movement {
     Forward(isize),
     Down(isize),
     Up(isize),
 }
 
+impl FromStr for Movement {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (direction, n_str) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(s, s.chars().count(), "a direction and distance"))?;
+        let n = parse_token(s, n_str, "a distance")?;
+        match direction.to_lowercase().as_str() {
+            "forward" => Ok(Self::Forward(n)),
+            "down" => Ok(Self::Down(n)),
+            "up" => Ok(Self::Up(n)),
+            _ => Err(ParseError::token(s, direction, "forward, down or up")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert_eq, proptest};
 
-    static INPUT: &str = "forward 5
-down 5
-forward 8
-up 3
-down 8
-forward 2";
+    static INPUT: &str = include_str!("../../fixtures/day_02/1.txt");
 
     #[test]
     fn part_1() {
@@ -99,4 +118,29 @@ forward 2";
         let runner = Day02::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(900));
     }
+
+    #[test]
+    fn negative_product() {
+        let runner = Day02::new(["forward 3", "up 2"].iter().map(|s| s.to_string())).unwrap();
+        assert_eq!(runner.part_1(), Answer::Signed(-6));
+    }
+
+    #[test]
+    fn error_column_counts_characters() {
+        match Day02::new(std::iter::once("förward".to_string())) {
+            Err(AOCError::ParseError(e)) => assert_eq!((e.line, e.column), (1, 8)),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn aim_keeps_horizontal_position(seed: u64, size in 0..200usize) {
+            let input = Day02::generate(&mut Rng::new(seed), size);
+            let runner = Day02::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let position = runner.course(Position::apply);
+            let aimed_position = runner.course(Position::apply_with_aim);
+            prop_assert_eq!(position.horizontal, aimed_position.horizontal);
+        }
+    }
 }
@@ -1,21 +1,46 @@
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 use ndarray::{Array2, Axis};
 
 pub struct Day03 {
     input: Array2<Bit>,
+    skipped: usize,
 }
 
 impl Day for Day03 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
+        Self::new_with_mode(input, ParseMode::Lenient)
+    }
+
+    fn new_with_mode(
+        input: impl Iterator<Item = String>,
+        mode: ParseMode,
+    ) -> Result<Self, AOCError> {
         let input_lines: Vec<_> = input.collect();
-        let num_bits = input_lines[0].len();
-        let mut arr = Array2::default((input_lines.len(), num_bits));
-        for (i, n) in input_lines.iter().enumerate() {
-            for (j, c) in n.chars().enumerate() {
-                arr[[i, j]] = c.into();
+        let num_bits = input_lines
+            .first()
+            .ok_or_else(|| ParseError::end_of_input("a binary number").line(0))?
+            .chars()
+            .count();
+        let (rows, skipped) = parse_digit_grid(&input_lines, 2, "binary digit", mode)?;
+        // Lenient mode leaves missing digits as zero and treats any other character as a one
+        let mut arr = Array2::default((rows.len(), num_bits));
+        for (i, row) in rows.iter().enumerate() {
+            for (j, digit) in row.iter().enumerate() {
+                arr[[i, j]] = match digit {
+                    Some(0) => Bit::Zero,
+                    _ => Bit::One,
+                };
             }
         }
-        Ok(Day03 { input: arr })
+        Ok(Day03 {
+            input: arr,
+            skipped,
+        })
+    }
+
+    fn skipped(&self) -> usize {
+        self.skipped
     }
 
     fn part_1(&self) -> Answer {
@@ -43,6 +68,21 @@ impl Day for Day03 {
     }
 }
 
+impl Generate for Day03 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const WIDTH: usize = 12;
+        (0..size)
+            .map(|_| {
+                let mut line: String = (0..WIDTH)
+                    .map(|_| if rng.chance(0.5) { '1' } else { '0' })
+                    .collect();
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+}
+
 fn part_02_helper<F>(mut input: Array2<Bit>, pred: F) -> BinaryNumber
 where
     F: Fn(usize, usize) -> bool,
@@ -51,6 +91,10 @@ where
         let col = input.index_axis(Axis(1), j);
         let zeros = col.iter().filter(|&&v| v == Bit::Zero).count();
         let ones = col.len() - zeros;
+        // Keep duplicate numbers that all share this bit, rather than removing every one
+        if zeros == 0 || ones == 0 {
+            continue;
+        }
 
         let remove_pred = if pred(zeros, ones) {
             |v: Bit| v != Bit::One
@@ -120,8 +164,9 @@ impl std::ops::Not for BinaryNumber {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 enum Bit {
+    #[default]
     Zero,
     One,
 }
@@ -135,22 +180,6 @@ impl Bit {
     }
 }
 
-impl Default for Bit {
-    fn default() -> Self {
-        Self::Zero
-    }
-}
-
-impl From<char> for Bit {
-    fn from(c: char) -> Self {
-        match c {
-            '0' => Self::Zero,
-            '1' => Self::One,
-            _ => Self::One,
-        }
-    }
-}
-
 impl std::ops::Not for Bit {
     type Output = Self;
 
@@ -165,19 +194,9 @@ impl std::ops::Not for Bit {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
-    static INPUT: &str = "00100
-11110
-10110
-10111
-10101
-01111
-00111
-11100
-10000
-11001
-00010
-01010";
+    static INPUT: &str = include_str!("../../fixtures/day_03/1.txt");
 
     #[test]
     fn part_1() {
@@ -190,4 +209,28 @@ mod test {
         let runner = Day03::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(230));
     }
+
+    #[test]
+    fn strict_mode() {
+        let input = ["101", "1x1", "10", "xx1"].iter().map(|s| s.to_string());
+        let runner = Day03::new(input.clone()).unwrap();
+        assert_eq!(runner.skipped(), 3);
+        match Day03::new_with_mode(input, ParseMode::Strict) {
+            Err(AOCError::ParseError(e)) => assert_eq!((e.line, e.column), (2, 2)),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn ratings_are_input_rows(seed: u64, size in 1..200usize) {
+            let input = Day03::generate(&mut Rng::new(seed), size);
+            let runner = Day03::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let rows: Vec<Vec<Bit>> = runner.input.outer_iter().map(|row| row.to_vec()).collect();
+            let o2 = part_02_helper(runner.input.clone(), |zeros, ones| ones >= zeros);
+            let co2 = part_02_helper(runner.input.clone(), |zeros, ones| zeros > ones);
+            prop_assert!(rows.contains(&o2.0));
+            prop_assert!(rows.contains(&co2.0));
+        }
+    }
 }
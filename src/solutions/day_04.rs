@@ -1,5 +1,6 @@
 use std::fmt;
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 use itertools::Itertools;
 use ndarray::{Array2, Axis};
@@ -10,17 +11,15 @@ pub struct Day04 {
 
 impl Day for Day04 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let mut input_iter = input;
+        let mut input_iter = input.enumerate();
 
         // Parse number order
-        let number_order = input_iter
+        let (_, line) = input_iter
             .next()
-            .ok_or(AOCError::ParseError)?
+            .ok_or_else(|| ParseError::end_of_input("the drawn numbers").line(0))?;
+        let number_order = line
             .split(',')
-            .map(|c| {
-                c.parse::<usize>()
-                    .map_err(|e| AOCError::ParseIntError(e, c.into()))
-            })
+            .map(|c| parse_token(&line, c, "a number").map_err(|e| e.line(0)))
             .collect::<Result<_, _>>()?;
 
         // Parse boards
@@ -30,7 +29,7 @@ impl Day for Day04 {
             .map(|chunk| {
                 let mut board: Array2<BingoCell> = Array2::default((5, 5));
                 // Parse one board
-                for (i, line) in chunk.skip(1).take(5).enumerate() {
+                for (i, (line_idx, line)) in chunk.skip(1).take(5).enumerate() {
                     // Parse one line
                     for (j, value_str) in line
                         .split(' ')
@@ -39,9 +38,8 @@ impl Day for Day04 {
                         .enumerate()
                     {
                         board[[i, j]] = BingoCell {
-                            value: value_str
-                                .parse()
-                                .map_err(|e| AOCError::ParseIntError(e, value_str.into()))?,
+                            value: parse_token(&line, value_str, "a number")
+                                .map_err(|e| e.line(line_idx))?,
                             marked: false,
                         }
                     }
@@ -49,7 +47,7 @@ impl Day for Day04 {
 
                 Ok(BingoBoard::new(board))
             })
-            .collect::<Result<_, _>>()?;
+            .collect::<Result<_, ParseError>>()?;
 
         Ok(Day04 {
             input: BingoInput {
@@ -95,6 +93,25 @@ impl Day for Day04 {
     }
 }
 
+impl Generate for Day04 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        // Every number is drawn, so every board wins eventually
+        let mut numbers: Vec<usize> = (0..100).collect();
+        rng.shuffle(&mut numbers);
+        let mut input = numbers.iter().join(",");
+        input.push('\n');
+        for _ in 0..size {
+            rng.shuffle(&mut numbers);
+            input.push('\n');
+            for row in numbers[..25].chunks(5) {
+                input += &row.iter().map(|n| format!("{:2}", n)).join(" ");
+                input.push('\n');
+            }
+        }
+        input
+    }
+}
+
 #[derive(Clone)]
 struct BingoInput {
     boards: Vec<BingoBoard>,
@@ -193,26 +210,9 @@ enum GameState {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert_eq, prop_assert_ne, proptest};
 
-    static INPUT: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
-
-22 13 17 11  0
- 8  2 23  4 24
-21  9 14 16  7
- 6 10  3 18  5
- 1 12 20 15 19
-
- 3 15  0  2 22
- 9 18 13 17  5
-19  8  7 25 23
-20 11 10 24  4
-14 21 16 12  6
-
-14 21 17 24  4
-10 16 15  9 19
-18  8 23 26 20
-22 11 13  6  5
- 2  0 12  3  7";
+    static INPUT: &str = include_str!("../../fixtures/day_04/1.txt");
 
     #[test]
     fn part_1() {
@@ -225,4 +225,14 @@ mod test {
         let runner = Day04::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(1924));
     }
+
+    proptest! {
+        #[test]
+        fn single_board_wins_first_and_last(seed: u64) {
+            let input = Day04::generate(&mut Rng::new(seed), 1);
+            let runner = Day04::new(input.lines().map(|s| s.to_owned())).unwrap();
+            prop_assert_ne!(runner.part_1(), Answer::None);
+            prop_assert_eq!(runner.part_1(), runner.part_2());
+        }
+    }
 }
@@ -1,57 +1,70 @@
 use rustc_hash::FxHashMap as HashMap;
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day05 {
-    input: Vec<Line>,
+    lines: Vec<Line>,
+    skipped: usize,
 }
 
 impl Day for Day05 {
-    #[allow(clippy::match_ref_pats)]
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let parsed: Vec<_> = input
-            .filter_map(|line_str| {
-                // Parse line in form "a,b -> x,y"
-                if let points_str @ &[_, _] = line_str.split(" -> ").collect::<Vec<_>>().as_slice()
-                {
-                    // Parse point in form "a,b"
-                    let points = points_str
-                        .iter()
-                        .filter_map(|point_str| {
-                            if let &[x_str, y_str] =
-                                point_str.split(',').collect::<Vec<_>>().as_slice()
-                            {
-                                // Parse x/y coordinate
-                                let x = x_str.parse().ok()?;
-                                let y = y_str.parse().ok()?;
-
-                                return Some(Point { x, y });
-                            }
-
-                            None
-                        })
-                        .collect::<Vec<_>>();
-
-                    if let &[p1, p2] = points.as_slice() {
-                        return Some(Line { p1, p2 });
-                    }
-                }
+        Self::new_with_mode(input, ParseMode::Lenient)
+    }
 
-                None
-            })
-            .collect();
+    fn new_with_mode(
+        input: impl Iterator<Item = String>,
+        mode: ParseMode,
+    ) -> Result<Self, AOCError> {
+        let mut lines = Vec::new();
+        let mut skipped = 0;
+        for (i, line_str) in input.enumerate() {
+            match Line::parse(&line_str) {
+                Ok(line) => lines.push(line),
+                Err(e) => mode.skip(&mut skipped, || e.line(i))?,
+            }
+        }
+        Ok(Day05 { lines, skipped })
+    }
 
-        Ok(Day05 { input: parsed })
+    fn skipped(&self) -> usize {
+        self.skipped
     }
 
     fn part_1(&self) -> Answer {
-        let grid = Grid::with_lines(&self.input, AllowDiagonals::No);
-        Answer::Integer(grid.overlaps())
+        Answer::Integer(Grid::with_lines(&self.lines, AllowDiagonals::No).overlaps())
     }
 
     fn part_2(&self) -> Answer {
-        let grid = Grid::with_lines(&self.input, AllowDiagonals::Yes);
-        Answer::Integer(grid.overlaps())
+        Answer::Integer(Grid::with_lines(&self.lines, AllowDiagonals::Yes).overlaps())
+    }
+}
+
+impl Generate for Day05 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const EXTENT: usize = 1000;
+        (0..size)
+            .map(|_| {
+                let (x1, y1) = (rng.range(0..EXTENT), rng.range(0..EXTENT));
+                let (x2, y2) = match rng.range(0..3) {
+                    0 => (rng.range(0..EXTENT), y1),
+                    1 => (x1, rng.range(0..EXTENT)),
+                    _ => {
+                        // Diagonals are at 45 degrees and stay inside the extent
+                        let (right, down) = (rng.chance(0.5), rng.chance(0.5));
+                        let room_x = if right { EXTENT - 1 - x1 } else { x1 };
+                        let room_y = if down { EXTENT - 1 - y1 } else { y1 };
+                        let len = rng.range(0..room_x.min(room_y) + 1);
+                        (
+                            if right { x1 + len } else { x1 - len },
+                            if down { y1 + len } else { y1 - len },
+                        )
+                    }
+                };
+                format!("{},{} -> {},{}\n", x1, y1, x2, y2)
+            })
+            .collect()
     }
 }
 
@@ -60,18 +73,12 @@ struct Grid(HashMap<Point, usize>);
 impl Grid {
     fn with_lines(lines: &[Line], diagonals: AllowDiagonals) -> Self {
         let mut grid = Self(HashMap::default());
-        for line in lines {
-            grid.add_line(line, diagonals);
+        for point in lines.iter().flat_map(|l| l.intermediate_points(diagonals)) {
+            grid.0.entry(point).and_modify(|v| *v += 1).or_insert(1);
         }
         grid
     }
 
-    fn add_line(&mut self, line: &Line, diagonals: AllowDiagonals) {
-        for point in line.intermediate_points(diagonals) {
-            self.0.entry(point).and_modify(|v| *v += 1).or_insert(1);
-        }
-    }
-
     fn overlaps(&self) -> usize {
         self.0
             .iter()
@@ -86,6 +93,17 @@ struct Line {
 }
 
 impl Line {
+    // Parse line in form "a,b -> x,y"
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        let (p1, p2) = s
+            .split_once(" -> ")
+            .ok_or_else(|| ParseError::new(s, s.chars().count(), "\" -> \""))?;
+        Ok(Line {
+            p1: Point::parse(s, p1)?,
+            p2: Point::parse(s, p2)?,
+        })
+    }
+
     fn intermediate_points(&self, diagonal: AllowDiagonals) -> Vec<Point> {
         let vector = self.to_vector();
         if vector.x != 0 && vector.y != 0 && vector.x.abs() != vector.y.abs() {
@@ -120,6 +138,17 @@ struct Point {
 }
 
 impl Point {
+    // Parse point in form "a,b", which is a slice of `line`
+    fn parse(line: &str, s: &str) -> Result<Self, ParseError> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| ParseError::token(line, s, "a point \"x,y\""))?;
+        Ok(Point {
+            x: parse_token(line, x, "an integer")?,
+            y: parse_token(line, y, "an integer")?,
+        })
+    }
+
     fn num_intermediate_points(&self) -> isize {
         self.x.abs().max(self.y.abs()) + 1
     }
@@ -153,17 +182,9 @@ fn sign(n: isize) -> isize {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
-    static INPUT: &str = "0,9 -> 5,9
-8,0 -> 0,8
-9,4 -> 3,4
-2,2 -> 2,1
-7,0 -> 7,4
-6,4 -> 2,0
-0,9 -> 2,9
-3,4 -> 1,4
-0,0 -> 8,8
-5,5 -> 8,2";
+    static INPUT: &str = include_str!("../../fixtures/day_05/1.txt");
 
     #[test]
     fn part_1() {
@@ -176,4 +197,26 @@ mod test {
         let runner = Day05::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(12));
     }
+
+    #[test]
+    fn strict_mode() {
+        let input = INPUT.lines().map(|s| s.replace("3,4 -> 1,4", "3,4 -> 1;4"));
+        let runner = Day05::new(input.clone()).unwrap();
+        assert_eq!(runner.skipped(), 1);
+        match Day05::new_with_mode(input, ParseMode::Strict) {
+            Err(AOCError::ParseError(e)) => assert_eq!((e.line, e.column), (8, 8)),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn diagonals_never_remove_overlaps(seed: u64, size in 0..40usize) {
+            let input = Day05::generate(&mut Rng::new(seed), size);
+            let runner = Day05::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let overlaps = Grid::with_lines(&runner.lines, AllowDiagonals::No).overlaps();
+            let diagonal_overlaps = Grid::with_lines(&runner.lines, AllowDiagonals::Yes).overlaps();
+            prop_assert!(diagonal_overlaps >= overlaps);
+        }
+    }
 }
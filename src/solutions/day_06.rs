@@ -1,47 +1,79 @@
+use num_bigint::BigUint;
+
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day06 {
     input: Vec<usize>,
+    /// Days to simulate for each part
+    days: [usize; 2],
 }
 
 impl Day for Day06 {
     fn new(mut input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let parsed: Vec<_> = input
+        let line = input
             .next()
-            .ok_or(AOCError::ParseError)?
+            .ok_or_else(|| ParseError::end_of_input("a list of timers").line(0))?;
+        let parsed: Vec<_> = line
             .split(',')
-            .map(|s| s.parse().map_err(|e| AOCError::ParseIntError(e, s.into())))
+            .map(|s| parse_token(&line, s, "a timer").map_err(|e| e.line(0)))
             .collect::<Result<_, _>>()?;
-        Ok(Day06 { input: parsed })
+        Ok(Day06 {
+            input: parsed,
+            days: [80, 256],
+        })
     }
 
     fn part_1(&self) -> Answer {
-        Answer::Integer(simulate(&self.input, 80))
+        simulate(&self.input, self.days[0]).into()
     }
 
     fn part_2(&self) -> Answer {
-        Answer::Integer(simulate(&self.input, 256))
+        simulate(&self.input, self.days[1]).into()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, usize)> {
+        vec![("days_1", self.days[0]), ("days_2", self.days[1])]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: usize) -> bool {
+        match name {
+            "days_1" => self.days[0] = value,
+            "days_2" => self.days[1] = value,
+            _ => return false,
+        }
+        true
     }
 }
 
-fn simulate(input: &[usize], num_days: usize) -> usize {
+impl Generate for Day06 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let timers: Vec<_> = (0..size.max(1))
+            .map(|_| rng.range(1..6).to_string())
+            .collect();
+        format!("{}\n", timers.join(","))
+    }
+}
+
+// The population grows exponentially, so count fish with arbitrary precision
+fn simulate(input: &[usize], num_days: usize) -> BigUint {
     const NEW_FISH_TIMER: usize = 8;
     const RESET_FISH_TIMER: usize = 6;
 
-    let mut fish: Vec<usize> = vec![0; NEW_FISH_TIMER + 1];
+    let mut fish: Vec<BigUint> = vec![BigUint::default(); NEW_FISH_TIMER + 1];
     for &x in input {
-        fish[x] += 1;
+        fish[x] += 1u32;
     }
 
     for _ in 0..num_days {
-        let num_new_fish = fish[0];
+        let num_new_fish = fish[0].clone();
 
         // Decrease all fish timers by 1
         fish.rotate_left(1);
 
         // Add new fish
+        fish[RESET_FISH_TIMER] += &num_new_fish;
         fish[NEW_FISH_TIMER] = num_new_fish;
-        fish[RESET_FISH_TIMER] += num_new_fish
     }
 
     fish.iter().sum()
@@ -50,8 +82,9 @@ fn simulate(input: &[usize], num_days: usize) -> usize {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, prop_assert_eq, proptest};
 
-    static INPUT: &str = "3,4,3,1,2";
+    static INPUT: &str = include_str!("../../fixtures/day_06/1.txt");
 
     #[test]
     fn part_1() {
@@ -64,4 +97,19 @@ mod test {
         let runner = Day06::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(26984457539));
     }
+
+    #[test]
+    fn beyond_usize() {
+        assert!(simulate(&[3, 4, 3, 1, 2], 1000) > BigUint::from(usize::MAX));
+    }
+
+    proptest! {
+        #[test]
+        fn population_never_shrinks(seed: u64, size in 1..50usize, days in 0..300usize) {
+            let input = Day06::generate(&mut Rng::new(seed), size);
+            let runner = Day06::new(input.lines().map(|s| s.to_owned())).unwrap();
+            prop_assert_eq!(simulate(&runner.input, 0), BigUint::from(runner.input.len()));
+            prop_assert!(simulate(&runner.input, days + 1) >= simulate(&runner.input, days));
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day07 {
@@ -6,11 +7,12 @@ pub struct Day07 {
 
 impl Day for Day07 {
     fn new(mut input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let parsed: Vec<_> = input
+        let line = input
             .next()
-            .ok_or(AOCError::ParseError)?
+            .ok_or_else(|| ParseError::end_of_input("a list of positions").line(0))?;
+        let parsed: Vec<_> = line
             .split(',')
-            .map(|s| s.parse().map_err(|e| AOCError::ParseIntError(e, s.into())))
+            .map(|s| parse_token(&line, s, "a position").map_err(|e| e.line(0)))
             .collect::<Result<_, _>>()?;
         Ok(Day07 { input: parsed })
     }
@@ -26,6 +28,15 @@ impl Day for Day07 {
     }
 }
 
+impl Generate for Day07 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let positions: Vec<_> = (0..size.max(1))
+            .map(|_| rng.range(0..2000).to_string())
+            .collect();
+        format!("{}\n", positions.join(","))
+    }
+}
+
 fn calculate<F>(input: &[usize], f: F) -> usize
 where
     F: Fn(usize, usize) -> usize,
@@ -50,8 +61,10 @@ fn triangular(n: usize) -> usize {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::{prop_assert, proptest};
 
-    static INPUT: &str = "16,1,2,0,4,2,7,1,2,14";
+    static INPUT: &str = include_str!("../../fixtures/day_07/1.txt");
 
     #[test]
     fn part_1() {
@@ -64,4 +77,15 @@ mod test {
         let runner = Day07::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(168));
     }
+
+    proptest! {
+        #[test]
+        fn increasing_cost_needs_more_fuel(input in vec(0..2000usize, 1..100)) {
+            let runner = Day07 { input };
+            let fuel = (runner.part_1(), runner.part_2());
+            prop_assert!(
+                matches!(fuel, (Answer::Integer(fuel_1), Answer::Integer(fuel_2)) if fuel_2 >= fuel_1)
+            );
+        }
+    }
 }
@@ -1,35 +1,41 @@
 use rustc_hash::FxHashSet as HashSet;
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day08 {
     input: Vec<Entry>,
+    skipped: usize,
 }
 
 impl Day for Day08 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
+        Self::new_with_mode(input, ParseMode::Lenient)
+    }
+
+    fn new_with_mode(
+        input: impl Iterator<Item = String>,
+        mode: ParseMode,
+    ) -> Result<Self, AOCError> {
+        let mut skipped = 0;
         let parsed: Vec<_> = input
-            .map(|s| {
-                let (signals, outputs) = if let [signals_str, outputs_str] =
-                    *s.split(" | ").take(2).collect::<Vec<_>>()
-                {
-                    // Parse signals
-                    let signals = signals_str
-                        .split(' ')
-                        .map(|s| s.chars().map(WireSegment::from).collect::<Pattern>())
-                        .collect::<Vec<_>>();
-                    let outputs = outputs_str
-                        .split(' ')
-                        .map(|s| s.chars().map(WireSegment::from).collect::<Pattern>())
-                        .collect::<Vec<_>>();
-                    (signals, outputs)
-                } else {
-                    (vec![], vec![])
-                };
-                Entry { signals, outputs }
+            .enumerate()
+            .map(|(i, s)| match parse_entry(&s) {
+                Ok(entry) => Ok(entry),
+                Err(e) => {
+                    mode.skip(&mut skipped, || e.line(i))?;
+                    Ok(lenient_entry(&s))
+                }
             })
-            .collect();
-        Ok(Day08 { input: parsed })
+            .collect::<Result<_, ParseError>>()?;
+        Ok(Day08 {
+            input: parsed,
+            skipped,
+        })
+    }
+
+    fn skipped(&self) -> usize {
+        self.skipped
     }
 
     fn part_1(&self) -> Answer {
@@ -123,6 +129,32 @@ impl Day for Day08 {
     }
 }
 
+impl Generate for Day08 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const DIGITS: [&str; 10] = [
+            "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+        ];
+        (0..size)
+            .map(|_| {
+                // Scramble the wires, then list every digit and four outputs in random orders
+                let mut wires: Vec<char> = ('a'..='g').collect();
+                rng.shuffle(&mut wires);
+                let output_digits: Vec<_> = (0..4).map(|_| DIGITS[rng.range(0..10)]).collect();
+                let mut scrambled = |digit: &str| {
+                    let mut pattern: Vec<char> =
+                        digit.bytes().map(|b| wires[(b - b'a') as usize]).collect();
+                    rng.shuffle(&mut pattern);
+                    pattern.into_iter().collect::<String>()
+                };
+                let mut signals: Vec<_> = DIGITS.iter().map(|d| scrambled(d)).collect();
+                let outputs: Vec<_> = output_digits.into_iter().map(scrambled).collect();
+                rng.shuffle(&mut signals);
+                format!("{} | {}\n", signals.join(" "), outputs.join(" "))
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug)]
 struct Entry {
     signals: Vec<Pattern>,
@@ -153,36 +185,92 @@ enum WireSegment {
     G,
 }
 
-impl From<char> for WireSegment {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for WireSegment {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'a' => Self::A,
-            'b' => Self::B,
-            'c' => Self::C,
-            'd' => Self::D,
-            'e' => Self::E,
-            'f' => Self::F,
-            'g' => Self::G,
-            _ => Self::A,
+            'a' => Ok(Self::A),
+            'b' => Ok(Self::B),
+            'c' => Ok(Self::C),
+            'd' => Ok(Self::D),
+            'e' => Ok(Self::E),
+            'f' => Ok(Self::F),
+            'g' => Ok(Self::G),
+            c => Err(c),
         }
     }
 }
 
+// Parse the 10 signal patterns and 4 output patterns of an entry, separated by " | "
+fn parse_entry(line: &str) -> Result<Entry, ParseError> {
+    let (signals, outputs) = line
+        .split_once(" | ")
+        .ok_or_else(|| ParseError::new(line, line.chars().count(), "\" | \""))?;
+    Ok(Entry {
+        signals: parse_patterns(line, signals, 10)?,
+        outputs: parse_patterns(line, outputs, 4)?,
+    })
+}
+
+// Keep what can be read of a malformed line, any other character is a segment A and a line
+// without a separator has no patterns
+fn lenient_entry(line: &str) -> Entry {
+    let patterns = |s: &str| {
+        s.split(' ')
+            .map(|word| {
+                word.chars()
+                    .map(|c| WireSegment::try_from(c).unwrap_or(WireSegment::A))
+                    .collect()
+            })
+            .collect()
+    };
+    match line.split_once(" | ") {
+        Some((signals, outputs)) => Entry {
+            signals: patterns(signals),
+            // Anything after a second separator is ignored
+            outputs: patterns(outputs.split(" | ").next().unwrap_or_default()),
+        },
+        None => Entry {
+            signals: vec![],
+            outputs: vec![],
+        },
+    }
+}
+
+// Parse exactly `count` space separated patterns from a slice of `line`
+fn parse_patterns(line: &str, patterns: &str, count: usize) -> Result<Vec<Pattern>, ParseError> {
+    let parsed = patterns
+        .split(' ')
+        .map(|word| {
+            if word.is_empty() {
+                return Err(ParseError::token(line, word, "a pattern"));
+            }
+            word.char_indices()
+                .map(|(j, c)| {
+                    WireSegment::try_from(c).map_err(|_| {
+                        ParseError::token(line, &word[j..j + c.len_utf8()], "a segment from a to g")
+                    })
+                })
+                .collect()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match parsed.len() == count {
+        true => Ok(parsed),
+        false => Err(ParseError::token(
+            line,
+            patterns,
+            format!("{} patterns", count),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert_eq, proptest};
 
-    static INPUT: &str =
-        "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
-edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
-fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
-fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
-aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
-fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
-dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
-bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
-egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
-gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
+    static INPUT: &str = include_str!("../../fixtures/day_08/1.txt");
 
     #[test]
     fn part_1() {
@@ -195,4 +283,72 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
         let runner = Day08::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(61229));
     }
+
+    #[test]
+    fn strict_mode() {
+        let input = INPUT.lines().map(|s| s.replacen(" | ", " ", 1));
+        let runner = Day08::new(input.clone()).unwrap();
+        assert_eq!(runner.skipped(), 10);
+        match Day08::new_with_mode(input, ParseMode::Strict) {
+            Err(AOCError::ParseError(e)) => assert_eq!(e.line, 1),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn strict_mode_patterns() {
+        let line = INPUT.lines().next().unwrap();
+        for (bad, column) in [
+            (line.replacen("be", "bx", 1), 2),
+            (line.replacen("fdgacbe ", "", 1), 62),
+        ] {
+            let runner = Day08::new(std::iter::once(bad.clone())).unwrap();
+            assert_eq!(runner.skipped(), 1);
+            match Day08::new_with_mode(std::iter::once(bad), ParseMode::Strict) {
+                Err(AOCError::ParseError(e)) => assert_eq!((e.line, e.column), (1, column)),
+                _ => panic!("expected a parse error"),
+            }
+        }
+    }
+
+    #[test]
+    fn lenient_mode_keeps_lines() {
+        // Unknown segments read as a, like before strict mode existed
+        let lines = |bad: char| {
+            INPUT.lines().enumerate().map(move |(i, s)| match i {
+                0 => s.replacen("be", &format!("b{}", bad), 1),
+                3 => s.replacen("cbd", &format!("c{}d", bad), 1),
+                _ => s.to_owned(),
+            })
+        };
+        let runner = Day08::new(lines('x')).unwrap();
+        let relabelled = Day08::new_with_mode(lines('a'), ParseMode::Strict).unwrap();
+        assert_eq!(runner.skipped(), 2);
+        assert_eq!(runner.part_1(), Answer::Integer(26));
+        assert_eq!(runner.part_2(), Answer::Integer(61129));
+        assert_eq!(runner.part_1(), relabelled.part_1());
+        assert_eq!(runner.part_2(), relabelled.part_2());
+    }
+
+    proptest! {
+        #[test]
+        fn wire_labels_dont_matter(seed: u64, size in 0..50usize) {
+            let mut rng = Rng::new(seed);
+            let input = Day08::generate(&mut rng, size);
+            let mut wires: Vec<char> = ('a'..='g').collect();
+            rng.shuffle(&mut wires);
+            let relabelled: String = input
+                .chars()
+                .map(|c| match c {
+                    'a'..='g' => wires[c as usize - 'a' as usize],
+                    c => c,
+                })
+                .collect();
+
+            let runner = Day08::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let relabelled = Day08::new(relabelled.lines().map(|s| s.to_owned())).unwrap();
+            prop_assert_eq!(runner.part_1(), relabelled.part_1());
+            prop_assert_eq!(runner.part_2(), relabelled.part_2());
+        }
+    }
 }
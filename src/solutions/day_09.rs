@@ -1,22 +1,43 @@
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 use ndarray::Array2;
 
 pub struct Day09 {
     input: Array2<usize>,
+    skipped: usize,
 }
 
 impl Day for Day09 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
+        Self::new_with_mode(input, ParseMode::Lenient)
+    }
+
+    fn new_with_mode(
+        input: impl Iterator<Item = String>,
+        mode: ParseMode,
+    ) -> Result<Self, AOCError> {
         let input: Vec<_> = input.collect();
-        let line_len = input.get(0).ok_or(AOCError::ParseError)?.len();
-        let mut arr = Array2::<usize>::from_elem((input.len() + 2, line_len + 2), usize::MAX);
-        for i in 0..input.len() {
-            for j in 0..line_len {
-                let chars: Vec<_> = input[i].chars().map(|c| c.to_string()).collect();
-                arr[[i + 1, j + 1]] = chars[j].parse().unwrap_or(0);
+        let line_len = input
+            .first()
+            .ok_or_else(|| ParseError::end_of_input("a row of heights").line(0))?
+            .chars()
+            .count();
+        let (rows, skipped) = parse_digit_grid(&input, 10, "height", mode)?;
+        // Lenient mode treats missing heights like the border and any other character as 0
+        let mut arr = Array2::<usize>::from_elem((rows.len() + 2, line_len + 2), usize::MAX);
+        for (i, row) in rows.iter().enumerate() {
+            for (j, height) in row.iter().enumerate() {
+                arr[[i + 1, j + 1]] = height.map_or(0, usize::from);
             }
         }
-        Ok(Day09 { input: arr })
+        Ok(Day09 {
+            input: arr,
+            skipped,
+        })
+    }
+
+    fn skipped(&self) -> usize {
+        self.skipped
     }
 
     fn part_1(&self) -> Answer {
@@ -32,12 +53,42 @@ impl Day for Day09 {
             .iter()
             .map(|point| basin_size(&self.input, *point))
             .collect();
-        basins[..].select_nth_unstable_by(3, |a, b| b.cmp(a)); // reverse sort
+        // Largest first, maps with fewer than three basins multiply the ones they have
+        basins.sort_unstable_by(|a, b| b.cmp(a));
         let product: usize = basins.iter().take(3).product();
         Answer::Integer(product)
     }
 }
 
+impl Generate for Day09 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        // Walls of 9s split the map into basins. Heights rise away from one random point in each
+        // basin, so it is the basin's only low point.
+        const BASIN: usize = 4;
+        let side = size.max(2 * BASIN);
+        let mut grid = vec![vec![9; side]; side];
+        for top in (0..side).step_by(BASIN) {
+            for left in (0..side).step_by(BASIN) {
+                let rows = top..(top + BASIN - 1).min(side);
+                let cols = left..(left + BASIN - 1).min(side);
+                let low = (rng.range(rows.clone()), rng.range(cols.clone()));
+                for i in rows {
+                    for j in cols.clone() {
+                        let distance = i.abs_diff(low.0) + j.abs_diff(low.1);
+                        grid[i][j] = match distance {
+                            0 => 0,
+                            d => (d + rng.range(0..2)).min(8),
+                        };
+                    }
+                }
+            }
+        }
+        grid.iter()
+            .map(|row| row.iter().map(|h| h.to_string()).collect::<String>() + "\n")
+            .collect()
+    }
+}
+
 fn low_points(input: &Array2<usize>) -> Vec<(usize, usize)> {
     input
         .windows([3, 3])
@@ -90,12 +141,9 @@ fn basin_size(input: &Array2<usize>, point: (usize, usize)) -> usize {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert_eq, proptest};
 
-    static INPUT: &str = "2199943210
-3987894921
-9856789892
-8767896789
-9899965678";
+    static INPUT: &str = include_str!("../../fixtures/day_09/1.txt");
 
     #[test]
     fn part_1() {
@@ -108,4 +156,34 @@ mod test {
         let runner = Day09::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(1134));
     }
+
+    #[test]
+    fn fewer_than_three_basins() {
+        let runner = Day09::new(["191", "999"].iter().map(|s| s.to_string())).unwrap();
+        assert_eq!(runner.part_2(), Answer::Integer(1));
+    }
+
+    #[test]
+    fn strict_mode() {
+        let input = INPUT.lines().map(|s| s.replace("3987894921", "39878949a1"));
+        let runner = Day09::new(input.clone()).unwrap();
+        assert_eq!(runner.skipped(), 1);
+        match Day09::new_with_mode(input, ParseMode::Strict) {
+            Err(AOCError::ParseError(e)) => assert_eq!((e.line, e.column), (2, 9)),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn basins_cover_every_cell_below_9(seed: u64, size in 1..50usize) {
+            let input = Day09::generate(&mut Rng::new(seed), size);
+            let runner = Day09::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let basins: usize = low_points(&runner.input)
+                .into_iter()
+                .map(|point| basin_size(&runner.input, point))
+                .sum();
+            prop_assert_eq!(basins, runner.input.iter().filter(|&&h| h < 9).count());
+        }
+    }
 }
@@ -1,47 +1,53 @@
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day10 {
-    input: Vec<Vec<Character>>,
+    lines: Vec<Vec<Character>>,
 }
 
 impl Day for Day10 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let parsed: Result<_, _> = input
-            .map(|s| s.chars().map(|c| c.try_into()).collect())
-            .collect();
-        Ok(Day10 { input: parsed? })
+        let lines = input
+            .enumerate()
+            .map(|(i, s)| {
+                s.chars()
+                    .enumerate()
+                    .map(|(j, c)| {
+                        Character::try_from(c)
+                            .map_err(|_| ParseError::new(&s, j, "a bracket").line(i))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Day10 { lines })
     }
 
     fn part_1(&self) -> Answer {
-        let points: usize = filter_lines(&self.input, LineStatus::Corrupted)
+        let corrupted_points = self
+            .lines
             .iter()
-            .filter_map(|c| {
-                if let BadLineChars::Corrupted(c) = c {
-                    Some(c.char_type.corrupt_point_value())
-                } else {
-                    None
-                }
+            .filter_map(|line| match check_line(line) {
+                Some(BadLineChars::Corrupted(c)) => Some(c.char_type.corrupt_point_value()),
+                _ => None,
             })
             .sum();
-        Answer::Integer(points)
+        Answer::Integer(corrupted_points)
     }
 
     fn part_2(&self) -> Answer {
-        let mut scores: Vec<_> = filter_lines(&self.input, LineStatus::Incomplete)
+        let mut scores: Vec<usize> = self
+            .lines
             .iter()
-            .filter_map(|c| {
-                if let BadLineChars::Incomplete(chars) = c {
-                    let score = chars
+            .filter_map(|line| match check_line(line) {
+                Some(BadLineChars::Incomplete(chars)) => Some(
+                    chars
                         .iter()
                         .rev()
-                        .fold(0, |acc, x| 5 * acc + x.char_type.incomplete_point_value());
-                    Some(score)
-                } else {
-                    None
-                }
+                        .fold(0, |acc, x| 5 * acc + x.char_type.incomplete_point_value()),
+                ),
+                _ => None,
             })
             .collect();
-
         if scores.is_empty() {
             return Answer::None;
         }
@@ -53,47 +59,71 @@ impl Day for Day10 {
     }
 }
 
-fn filter_lines(input: &[Vec<Character>], status: LineStatus) -> Vec<BadLineChars> {
-    input
-        .iter()
-        .filter_map(|line| {
-            let mut stack = Vec::new();
-            for c in line {
-                match c.open_close {
-                    OpenClose::Open => stack.push(c),
-                    OpenClose::Close => {
-                        if let Some(c_stack) = stack.pop() {
-                            if c_stack.char_type == c.char_type
-                                && c_stack.open_close == OpenClose::Open
-                            {
-                                // Matching bracket, do next character
-                                continue;
-                            }
-                        }
-
-                        // Corrupt
-                        if status == LineStatus::Corrupted {
-                            return Some(BadLineChars::Corrupted(c));
-                        }
-
-                        return None;
+impl Generate for Day10 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const OPEN: [char; 4] = ['(', '[', '{', '<'];
+        const CLOSE: [char; 4] = [')', ']', '}', '>'];
+        // Short enough that completion scores fit in a usize
+        const MAX_DEPTH: usize = 12;
+
+        (0..size)
+            .map(|i| {
+                // Chunks that are either incomplete or corrupted. The first line is incomplete so
+                // part 2 always has an answer.
+                let mut open = Vec::new();
+                let mut line = String::new();
+                for _ in 0..rng.range(10..100) {
+                    if open.len() == MAX_DEPTH || (!open.is_empty() && rng.chance(0.45)) {
+                        line.push(CLOSE[open.pop().unwrap()]);
+                    } else {
+                        let kind = rng.range(0..4);
+                        open.push(kind);
+                        line.push(OPEN[kind]);
                     }
                 }
-            }
+                if open.is_empty() {
+                    let kind = rng.range(0..4);
+                    open.push(kind);
+                    line.push(OPEN[kind]);
+                }
+                if i > 0 && rng.chance(0.5) {
+                    let expected = open[open.len() - 1];
+                    line.push(CLOSE[(expected + rng.range(1..4)) % 4]);
+                }
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+}
 
-            // Complete
-            if stack.is_empty() {
-                return None;
-            }
+// Find the first corrupted character or the unclosed characters of a line, if any
+fn check_line(line: &[Character]) -> Option<BadLineChars<'_>> {
+    let mut stack = Vec::new();
+    for c in line {
+        match c.open_close {
+            OpenClose::Open => stack.push(c),
+            OpenClose::Close => {
+                if let Some(c_stack) = stack.pop() {
+                    if c_stack.char_type == c.char_type && c_stack.open_close == OpenClose::Open {
+                        // Matching bracket, do next character
+                        continue;
+                    }
+                }
 
-            // Incomplete
-            if status == LineStatus::Incomplete {
-                return Some(BadLineChars::Incomplete(stack));
+                // Corrupt
+                return Some(BadLineChars::Corrupted(c));
             }
+        }
+    }
 
-            None
-        })
-        .collect()
+    // Complete
+    if stack.is_empty() {
+        return None;
+    }
+
+    // Incomplete
+    Some(BadLineChars::Incomplete(stack))
 }
 
 #[derive(Debug)]
@@ -131,7 +161,7 @@ impl CharacterType {
 }
 
 impl TryFrom<char> for Character {
-    type Error = AOCError;
+    type Error = char;
 
     fn try_from(c: char) -> Result<Self, Self::Error> {
         use CharacterType::*;
@@ -170,7 +200,7 @@ impl TryFrom<char> for Character {
                 char_type: AngleBracket,
                 open_close: Close,
             }),
-            _ => Err(AOCError::ParseError),
+            _ => Err(c),
         }
     }
 }
@@ -181,12 +211,6 @@ enum OpenClose {
     Close,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum LineStatus {
-    Incomplete,
-    Corrupted,
-}
-
 enum BadLineChars<'a> {
     Incomplete(Vec<&'a Character>),
     Corrupted(&'a Character),
@@ -195,17 +219,9 @@ enum BadLineChars<'a> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
-    static INPUT: &str = "[({(<(())[]>[[{[]{<()<>>
-[(()[<>])]({[<{<<[]>>(
-{([(<{}[<>[]}>{[]{[(<()>
-(((({<>}<{<{<>}{[]{[]{}
-[[<[([]))<([[{}[[()]]]
-[{[{({}]{}}([{[{{{}}([]
-{<[[]]>}<{[{[{[]{()[[[]
-[<(<(<(<{}))><([]([]()
-<{([([[(<>()){}]>(<<{{
-<{([{{}}[<[[[<>{}]]]>[]]";
+    static INPUT: &str = include_str!("../../fixtures/day_10/1.txt");
 
     #[test]
     fn part_1() {
@@ -218,4 +234,28 @@ mod test {
         let runner = Day10::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(288957));
     }
+
+    proptest! {
+        #[test]
+        fn completions_close_every_chunk(seed: u64, size in 1..50usize) {
+            let input = Day10::generate(&mut Rng::new(seed), size);
+            for line in input.lines() {
+                let chars: Vec<_> = line.chars().map(|c| Character::try_from(c).unwrap()).collect();
+                if let Some(BadLineChars::Incomplete(open)) = check_line(&chars) {
+                    let completion = open.iter().rev().map(|c| match c.char_type {
+                        CharacterType::Parenthesis => ')',
+                        CharacterType::SquareBracket => ']',
+                        CharacterType::CurlyBracket => '}',
+                        CharacterType::AngleBracket => '>',
+                    });
+                    let completed: Vec<_> = line
+                        .chars()
+                        .chain(completion)
+                        .map(|c| Character::try_from(c).unwrap())
+                        .collect();
+                    prop_assert!(check_line(&completed).is_none());
+                }
+            }
+        }
+    }
 }
@@ -1,50 +1,114 @@
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 use ndarray::Array2;
 
 pub struct Day11 {
     input: Array2<Option<Octopus>>,
+    /// Steps to count flashes over in part 1
+    steps: usize,
+    /// Steps to wait for the octopi to synchronize in part 2
+    step_limit: usize,
 }
 
 impl Day for Day11 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
         let lines: Vec<_> = input.collect();
         let num_lines = lines.len();
-        let line_len = lines.get(0).ok_or(AOCError::ParseError)?.len();
+        let line_len = lines
+            .first()
+            .ok_or_else(|| ParseError::end_of_input("a row of energy levels").line(0))?
+            .chars()
+            .count();
 
         let mut arr = Array2::from_elem((num_lines + 2, line_len + 2), None);
 
         for (i, line) in lines.iter().enumerate() {
             for (j, c) in line.chars().take(line_len).enumerate() {
-                let energy = c.to_digit(10).ok_or(AOCError::ParseError)? as usize;
+                let energy = c
+                    .to_digit(10)
+                    .ok_or_else(|| ParseError::new(line, j, "an energy level").line(i))?
+                    as usize;
                 arr[[i + 1, j + 1]] = Some(Octopus {
                     energy,
                     flashed: false,
                 });
             }
         }
-        Ok(Day11 { input: arr })
+        Ok(Day11 {
+            input: arr,
+            steps: 100,
+            step_limit: 100_000,
+        })
     }
 
     fn part_1(&self) -> Answer {
-        Answer::Integer(simulate(&self.input, EndCondition::Step(100)))
+        self.run_part(Part::One, &PartContext::default())
     }
 
     fn part_2(&self) -> Answer {
-        Answer::Integer(simulate(&self.input, EndCondition::Synchronized))
+        self.run_part(Part::Two, &PartContext::default())
+    }
+
+    fn run_part(&self, part: Part, ctx: &PartContext) -> Answer {
+        let end = match part {
+            Part::One => EndCondition::Step(self.steps),
+            Part::Two => EndCondition::Synchronized(self.step_limit),
+        };
+        match simulate(&self.input, end, ctx) {
+            Ok(Some(n)) => Answer::Integer(n),
+            Ok(None) => Answer::Error(Box::new(AOCError::StepLimit(self.step_limit))),
+            Err(e) => e.into(),
+        }
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, usize)> {
+        vec![("steps", self.steps), ("step_limit", self.step_limit)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: usize) -> bool {
+        match name {
+            "steps" => self.steps = value,
+            "step_limit" => self.step_limit = value,
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Generate for Day11 {
+    // Random grids may never synchronize, so part 2 gives up after `step_limit` steps
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let side = size.max(1);
+        (0..side)
+            .map(|_| {
+                (0..side)
+                    .map(|_| rng.range(0..10).to_string())
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
     }
 }
 
-fn simulate(input: &Array2<Option<Octopus>>, end: EndCondition) -> usize {
+// Flashes after a number of steps, or the first step when all octopi flash, if within the limit
+fn simulate(
+    input: &Array2<Option<Octopus>>,
+    end: EndCondition,
+    ctx: &PartContext,
+) -> Result<Option<usize>, Cancelled> {
     let mut arr_cur = input.clone();
     let mut arr_next;
     let mut flash_count = 0;
 
-    let end_step = match end {
-        EndCondition::Step(s) => s,
-        EndCondition::Synchronized => usize::MAX,
+    let total_steps = match end {
+        EndCondition::Step(s) | EndCondition::Synchronized(s) => s,
     };
 
-    for step in 0..end_step {
+    for step in 0..total_steps {
+        // The octopi may take many steps to synchronize, so stop when cancelled
+        ctx.check()?;
+        ctx.progress(step, Some(total_steps));
+
         // Increment all energy levels
         arr_cur.iter_mut().for_each(|x| {
             if let Some(o) = x {
@@ -96,8 +160,10 @@ fn simulate(input: &Array2<Option<Octopus>>, end: EndCondition) -> usize {
             }
         }
 
-        if end == EndCondition::Synchronized && arr_cur.iter().flatten().all(|o| o.flashed) {
-            return step + 1;
+        if matches!(end, EndCondition::Synchronized(_))
+            && arr_cur.iter().flatten().all(|o| o.flashed)
+        {
+            return Ok(Some(step + 1));
         }
 
         // Reset flashed octopi
@@ -110,8 +176,8 @@ fn simulate(input: &Array2<Option<Octopus>>, end: EndCondition) -> usize {
     }
 
     match end {
-        EndCondition::Step(_) => flash_count,
-        EndCondition::Synchronized => usize::MAX,
+        EndCondition::Step(_) => Ok(Some(flash_count)),
+        EndCondition::Synchronized(_) => Ok(None),
     }
 }
 
@@ -124,23 +190,16 @@ struct Octopus {
 #[derive(PartialEq, Eq)]
 enum EndCondition {
     Step(usize),
-    Synchronized,
+    /// Synchronized within a number of steps
+    Synchronized(usize),
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
-    static INPUT: &str = "5483143223
-2745854711
-5264556173
-6141336146
-6357385478
-4167524645
-2176841721
-6882881134
-4846848554
-5283751526";
+    static INPUT: &str = include_str!("../../fixtures/day_11/1.txt");
 
     #[test]
     fn part_1() {
@@ -153,4 +212,35 @@ mod test {
         let runner = Day11::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(195));
     }
+
+    #[test]
+    fn cancelled() {
+        let runner = Day11::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
+        let ctx = PartContext::default();
+        ctx.cancel.cancel();
+        assert_eq!(runner.run_part(Part::Two, &ctx), Answer::Timeout);
+    }
+
+    #[test]
+    fn step_limit() {
+        let mut runner = Day11::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
+        assert!(runner.set_parameter("step_limit", 195));
+        assert_eq!(runner.part_2(), Answer::Integer(195));
+        assert!(runner.set_parameter("step_limit", 194));
+        match runner.part_2() {
+            Answer::Error(e) => assert_eq!(e.to_string(), "No answer within 194 steps"),
+            answer => panic!("expected an error, got {:?}", answer),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn octopi_flash_at_most_once_per_step(seed: u64, size in 1..12usize, steps in 0..50usize) {
+            let input = Day11::generate(&mut Rng::new(seed), size);
+            let runner = Day11::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let ctx = PartContext::default();
+            let flashes = simulate(&runner.input, EndCondition::Step(steps), &ctx).unwrap().unwrap();
+            prop_assert!(flashes <= steps * size * size);
+        }
+    }
 }
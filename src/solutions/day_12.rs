@@ -1,6 +1,8 @@
 use rustc_hash::FxHashMap as HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 use rayon::prelude::*;
 
@@ -10,26 +12,92 @@ pub struct Day12 {
 
 impl Day for Day12 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let parsed = input.map(|line| line.parse()).collect::<Result<_, _>>()?;
+        let parsed = input
+            .enumerate()
+            .map(|(i, line)| line.parse().map_err(|e: ParseError| e.line(i)))
+            .collect::<Result<_, _>>()?;
         Ok(Day12 { input: parsed })
     }
 
     fn part_1(&self) -> Answer {
-        let adj = generate_adjacency(&self.input);
-        do_dfs(adj, |_| false).into()
+        self.run_part(Part::One, &PartContext::default())
     }
 
     fn part_2(&self) -> Answer {
+        self.run_part(Part::Two, &PartContext::default())
+    }
+
+    fn run_part(&self, part: Part, ctx: &PartContext) -> Answer {
         let adj = generate_adjacency(&self.input);
-        do_dfs(adj, |v| {
-            !v.iter()
-                .any(|(&c, &x)| matches!(c, Cave::Small(_)) && x > 1)
-        })
-        .into()
+        match part {
+            Part::One => do_dfs(adj, |_| false, ctx).into(),
+            Part::Two => do_dfs(
+                adj,
+                |v| {
+                    !v.iter()
+                        .any(|(&c, &x)| matches!(c, Cave::Small(_)) && x > 1)
+                },
+                ctx,
+            )
+            .into(),
+        }
+    }
+}
+
+impl Generate for Day12 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        fn connect<'a>(paths: &mut Vec<(&'a str, &'a str)>, a: &'a str, b: &'a str) {
+            if a != b && !paths.contains(&(a, b)) && !paths.contains(&(b, a)) {
+                paths.push((a, b));
+            }
+        }
+
+        let small = cave_names(rng, size.max(1), b'a');
+        let big = cave_names(rng, (size / 3).max(1), b'A');
+        let mut paths = Vec::new();
+        // Big caves only connect to small caves, otherwise there would be infinitely many paths
+        for cave in ["start", "end"]
+            .into_iter()
+            .chain(big.iter().map(String::as_str))
+        {
+            for _ in 0..2 {
+                connect(&mut paths, cave, rng.choose(&small).as_str());
+            }
+        }
+        for cave in &small {
+            let other = match rng.chance(0.5) {
+                true => rng.choose(&small),
+                false => rng.choose(&big),
+            };
+            connect(&mut paths, cave, other);
+        }
+        paths
+            .iter()
+            .map(|(a, b)| format!("{}-{}\n", a, b))
+            .collect()
+    }
+}
+
+// Distinct random cave names with letters starting from `first`
+fn cave_names(rng: &mut Rng, count: usize, first: u8) -> Vec<String> {
+    let len = if count < 100 { 2 } else { 4 };
+    let mut names = Vec::with_capacity(count);
+    while names.len() < count {
+        let name: String = (0..len)
+            .map(|_| (first + rng.range(0..26) as u8) as char)
+            .collect();
+        if !names.contains(&name) {
+            names.push(name);
+        }
     }
+    names
 }
 
-fn do_dfs<F>(adj: HashMap<&Cave, Vec<&Cave>>, small_criteria: F) -> Option<usize>
+fn do_dfs<F>(
+    adj: HashMap<&Cave, Vec<&Cave>>,
+    small_criteria: F,
+    ctx: &PartContext,
+) -> Option<Result<usize, Cancelled>>
 where
     F: Sync + Fn(&HashMap<&Cave, usize>) -> bool,
 {
@@ -39,13 +107,20 @@ where
         .map(|&c| Stack::new(c))
         .collect();
 
+    // Progress is reported as the number of searches from the start cave that have finished
+    let total = stacks.len();
+    let finished = AtomicUsize::new(0);
+
     let count = stacks
         .par_iter_mut()
         .map(|stack| {
             // DFS
             let mut count = 0;
             while let Some((cave, mut visited)) = stack.pop() {
-                if visited.get(cave).is_none()
+                // The number of paths can grow exponentially, so stop when cancelled
+                ctx.check()?;
+
+                if !visited.contains_key(cave)
                     || matches!(cave, Cave::Big(_))
                     || matches!(cave, Cave::Small(_)) && small_criteria(&visited)
                 {
@@ -63,7 +138,8 @@ where
                     }
                 }
             }
-            count
+            ctx.progress(finished.fetch_add(1, Ordering::Relaxed) + 1, Some(total));
+            Ok(count)
         })
         .sum();
 
@@ -73,14 +149,16 @@ where
 struct Path(Cave, Cave);
 
 impl FromStr for Path {
-    type Err = AOCError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let [a, b] = *s.split('-').collect::<Vec<_>>() {
-            Ok(Self(a.parse()?, b.parse()?))
-        } else {
-            Err(AOCError::ParseError)
+        let (a, b) = s
+            .split_once('-')
+            .ok_or_else(|| ParseError::new(s, s.chars().count(), "'-'"))?;
+        if let Some(j) = b.find('-') {
+            return Err(ParseError::token(s, &b[j..j + 1], "end of line"));
         }
+        Ok(Self(a.parse()?, b.parse()?))
     }
 }
 
@@ -93,7 +171,7 @@ enum Cave {
 }
 
 impl FromStr for Cave {
-    type Err = AOCError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s == "start" {
@@ -143,14 +221,9 @@ fn generate_adjacency(paths: &[Path]) -> HashMap<&Cave, Vec<&Cave>> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
-    static INPUT: &str = "start-A
-start-b
-A-c
-A-b
-b-d
-A-end
-b-end";
+    static INPUT: &str = include_str!("../../fixtures/day_12/1.txt");
 
     #[test]
     fn part_1() {
@@ -163,4 +236,16 @@ b-end";
         let runner = Day12::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(36));
     }
+
+    proptest! {
+        #[test]
+        fn revisiting_allows_more_paths(seed: u64, size in 1..6usize) {
+            let input = Day12::generate(&mut Rng::new(seed), size);
+            let runner = Day12::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let paths = (runner.part_1(), runner.part_2());
+            prop_assert!(
+                matches!(paths, (Answer::Integer(paths_1), Answer::Integer(paths_2)) if paths_2 >= paths_1)
+            );
+        }
+    }
 }
@@ -4,8 +4,7 @@ use std::io::Write;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
-use itertools::Itertools;
-
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day13 {
@@ -15,19 +14,29 @@ pub struct Day13 {
 
 impl Day for Day13 {
     fn new(mut input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let dots: Vec<Dot> = input.by_ref().map_while(|line| line.parse().ok()).collect();
-        let folds: Vec<Fold> = input.by_ref().map_while(|line| line.parse().ok()).collect();
-        if input.any(|line| !line.is_empty()) {
-            return Err(AOCError::ParseError);
+        let mut lines = input.by_ref().enumerate();
+
+        // Dots until the first blank line, then folds
+        let mut dots = Vec::new();
+        for (i, line) in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            dots.push(line.parse().map_err(|e: ParseError| e.line(i))?);
+        }
+        let mut folds = Vec::new();
+        for (i, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            folds.push(line.parse().map_err(|e: ParseError| e.line(i))?);
         }
+
         Ok(Day13 { dots, folds })
     }
 
     fn part_1(&self) -> Answer {
         self.folds
-            .get(0)
+            .first()
             .as_ref()
-            .map(|fold| do_fold(&Paper::from_dots(&self.dots[..]), *fold).num_dots())
+            .map(|fold| do_fold(&Paper::from_dots(&self.dots[..]), fold).num_dots())
             .into()
     }
 
@@ -39,6 +48,65 @@ impl Day for Day13 {
             })
             .into()
     }
+
+    fn views(&self) -> &'static [&'static str] {
+        &["paper"]
+    }
+
+    fn inspect(&self, view: &str, steps: usize) -> Option<String> {
+        if view != "paper" {
+            return None;
+        }
+        let paper = self
+            .folds
+            .iter()
+            .take(steps)
+            .fold(Paper::from_dots(&self.dots[..]), |acc, fold| {
+                do_fold(&acc, fold)
+            });
+        Some(format!("{} dots\n{}", paper.num_dots(), paper))
+    }
+}
+
+impl Generate for Day13 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        // Build the folds outwards from the final sheet, each doubling one side of the paper
+        let (mut width, mut height) = (40, 6);
+        let mut unfolds = Vec::new();
+        for _ in 0..rng.range(4..8) {
+            if rng.chance(0.5) {
+                unfolds.push(('x', width));
+                width = 2 * width + 1;
+            } else {
+                unfolds.push(('y', height));
+                height = 2 * height + 1;
+            }
+        }
+
+        // Dots start on the final sheet and may be mirrored across each fold, so none lie on a
+        // fold line
+        let mut dots = HashSet::default();
+        let mut input = String::new();
+        for _ in 0..size {
+            let (mut x, mut y) = (rng.range(0..40), rng.range(0..6));
+            for &(axis, at) in &unfolds {
+                if rng.chance(0.5) {
+                    match axis {
+                        'x' => x = 2 * at - x,
+                        _ => y = 2 * at - y,
+                    }
+                }
+            }
+            if dots.insert((x, y)) {
+                input += &format!("{},{}\n", x, y);
+            }
+        }
+        input.push('\n');
+        for (axis, at) in unfolds.iter().rev() {
+            input += &format!("fold along {}={}\n", axis, at);
+        }
+        input
+    }
 }
 
 fn do_fold(paper: &Paper, fold: &Fold) -> Paper {
@@ -152,19 +220,15 @@ struct Dot {
 }
 
 impl FromStr for Dot {
-    type Err = AOCError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((x, y)) = s.split(',').next_tuple() {
-            let x = x
-                .parse()
-                .map_err(|e| AOCError::ParseIntError(e, x.into()))?;
-            let y = y
-                .parse()
-                .map_err(|e| AOCError::ParseIntError(e, y.into()))?;
-            return Ok(Self { x, y });
-        }
-        Err(AOCError::ParseError)
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| ParseError::new(s, s.chars().count(), "','"))?;
+        let x = parse_token(s, x, "a coordinate")?;
+        let y = parse_token(s, y, "a coordinate")?;
+        Ok(Self { x, y })
     }
 }
 
@@ -175,50 +239,30 @@ enum Fold {
 }
 
 impl FromStr for Fold {
-    type Err = AOCError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some(s) = s.strip_prefix("fold along ") {
-            if let Some((axis, n_str)) = s.split('=').next_tuple() {
-                let n: isize = n_str
-                    .parse()
-                    .map_err(|e| AOCError::ParseIntError(e, n_str.into()))?;
-                match axis {
-                    "x" => return Ok(Self::X(n)),
-                    "y" => return Ok(Self::Y(n)),
-                    _ => (),
-                }
-            }
+        let fold = s
+            .strip_prefix("fold along ")
+            .ok_or_else(|| ParseError::new(s, 0, "\"fold along\""))?;
+        let (axis, n_str) = fold
+            .split_once('=')
+            .ok_or_else(|| ParseError::new(s, s.chars().count(), "'='"))?;
+        let n = parse_token(s, n_str, "a fold position")?;
+        match axis {
+            "x" => Ok(Self::X(n)),
+            "y" => Ok(Self::Y(n)),
+            _ => Err(ParseError::token(s, axis, "x or y")),
         }
-        Err(AOCError::ParseError)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
-    static INPUT: &str = "6,10
-0,14
-9,10
-0,3
-10,4
-4,11
-6,0
-6,12
-4,1
-0,13
-10,12
-3,4
-3,0
-8,4
-1,10
-2,14
-8,10
-9,0
-
-fold along y=7
-fold along x=5";
+    static INPUT: &str = include_str!("../../fixtures/day_13/1.txt");
 
     #[test]
     fn part_1() {
@@ -237,4 +281,18 @@ fold along x=5";
         let runner = Day13::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Printable(expected));
     }
+
+    proptest! {
+        #[test]
+        fn folding_never_adds_dots(seed: u64, size in 0..200usize) {
+            let input = Day13::generate(&mut Rng::new(seed), size);
+            let runner = Day13::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let mut paper = Paper::from_dots(&runner.dots);
+            for fold in &runner.folds {
+                let folded = do_fold(&paper, fold);
+                prop_assert!(folded.num_dots() <= paper.num_dots());
+                paper = folded;
+            }
+        }
+    }
 }
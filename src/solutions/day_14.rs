@@ -1,77 +1,166 @@
 use rustc_hash::FxHashMap as HashMap;
+use std::fmt;
 use std::ops::Deref;
 use std::str::FromStr;
 
 use itertools::{Itertools, MinMaxResult};
+use num_bigint::BigUint;
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day14 {
     template: Polymer,
     insertion_rules: InsertionRules,
+    /// Steps to simulate for each part
+    steps: [usize; 2],
 }
 
 impl Day for Day14 {
     fn new(mut input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let template = input.by_ref().next().ok_or(AOCError::ParseError)?.parse()?;
+        let template = input
+            .by_ref()
+            .next()
+            .ok_or_else(|| ParseError::end_of_input("a polymer template").line(0))?
+            .parse()?;
         input.next();
         let insertion_rules = input
-            .map(InsertionRule::try_from)
+            .enumerate()
+            .map(|(i, s)| InsertionRule::try_from(s).map_err(|e| e.line(i + 2)))
             .collect::<Result<Vec<_>, _>>()?
             .into_iter()
             .collect();
         Ok(Day14 {
             template,
             insertion_rules,
+            steps: [10, 40],
         })
     }
 
     fn part_1(&self) -> Answer {
-        simulate(&self.template, &self.insertion_rules, 10).into()
+        simulate(&self.template, &self.insertion_rules, self.steps[0]).into()
     }
 
     fn part_2(&self) -> Answer {
-        simulate(&self.template, &self.insertion_rules, 40).into()
+        simulate(&self.template, &self.insertion_rules, self.steps[1]).into()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, usize)> {
+        vec![("steps_1", self.steps[0]), ("steps_2", self.steps[1])]
     }
-}
 
-fn simulate(template: &Polymer, insertion_rules: &InsertionRules, steps: usize) -> Option<usize> {
-    // Character pair counts
-    let mut pairs = HashMap::default();
-    for (&a, &b) in template.iter().tuple_windows::<(_, _)>() {
-        *pairs.entry((a, b)).or_insert(0) += 1;
+    fn set_parameter(&mut self, name: &str, value: usize) -> bool {
+        match name {
+            "steps_1" => self.steps[0] = value,
+            "steps_2" => self.steps[1] = value,
+            _ => return false,
+        }
+        true
     }
 
-    // Individual character counts
-    let mut char_counts = HashMap::default();
-    for c in template.iter() {
-        *char_counts.entry(c).or_insert(0) += 1;
+    fn views(&self) -> &'static [&'static str] {
+        &["pairs"]
     }
 
+    fn inspect(&self, view: &str, steps: usize) -> Option<String> {
+        if view != "pairs" {
+            return None;
+        }
+        let mut counts = PairCounts::new(&self.template);
+        for _ in 0..steps {
+            counts.step(&self.insertion_rules);
+        }
+        Some(counts.to_string())
+    }
+}
+
+impl Generate for Day14 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut elements: Vec<char> = ('A'..='Z').collect();
+        rng.shuffle(&mut elements);
+        elements.truncate(rng.range(4..11));
+
+        let mut input: String = (0..size.max(2)).map(|_| *rng.choose(&elements)).collect();
+        input += "\n\n";
+        // A rule for every pair of elements, as in the puzzle input
+        for a in &elements {
+            for b in &elements {
+                input += &format!("{}{} -> {}\n", a, b, rng.choose(&elements));
+            }
+        }
+        input
+    }
+}
+
+// Counts grow exponentially with the number of steps, so use arbitrary precision
+fn simulate(template: &Polymer, insertion_rules: &InsertionRules, steps: usize) -> Option<BigUint> {
+    let mut counts = PairCounts::new(template);
     for _ in 0..steps {
-        let mut next_pairs = pairs.clone();
+        counts.step(insertion_rules);
+    }
+
+    match counts.elements.values().minmax() {
+        MinMaxResult::MinMax(min, max) => Some(max - min),
+        MinMaxResult::OneElement(_) => Some(BigUint::default()),
+        MinMaxResult::NoElements => None,
+    }
+}
+
+// A polymer as counts of its adjacent pairs and individual elements, since the order of pairs
+// doesn't affect insertions
+struct PairCounts {
+    pairs: HashMap<(char, char), BigUint>,
+    elements: HashMap<char, BigUint>,
+}
+
+impl PairCounts {
+    fn new(template: &Polymer) -> Self {
+        let mut pairs: HashMap<_, BigUint> = HashMap::default();
+        for (&a, &b) in template.iter().tuple_windows::<(_, _)>() {
+            *pairs.entry((a, b)).or_default() += 1u32;
+        }
+        let mut elements: HashMap<_, BigUint> = HashMap::default();
+        for &c in template.iter() {
+            *elements.entry(c).or_default() += 1u32;
+        }
+        Self { pairs, elements }
+    }
+
+    fn step(&mut self, insertion_rules: &InsertionRules) {
+        let mut next_pairs = self.pairs.clone();
 
         // Iterate over all pairs
-        for (pair, &count) in pairs.iter() {
+        for (pair, count) in self.pairs.iter() {
             // Check insertion rules
-            if let Some(c) = insertion_rules.get(pair) {
+            if let Some(&c) = insertion_rules.get(pair) {
                 // Remove current pair
-                *next_pairs.entry(*pair).or_insert(0) -= count;
+                *next_pairs.entry(*pair).or_default() -= count;
                 // Add new pairs
-                *next_pairs.entry((pair.0, *c)).or_insert(0) += count;
-                *next_pairs.entry((*c, pair.1)).or_insert(0) += count;
+                *next_pairs.entry((pair.0, c)).or_default() += count;
+                *next_pairs.entry((c, pair.1)).or_default() += count;
                 // Increment individual character counts
-                *char_counts.entry(c).or_insert(0) += count;
+                *self.elements.entry(c).or_default() += count;
             }
         }
 
-        pairs = next_pairs;
+        self.pairs = next_pairs;
     }
+}
 
-    match char_counts.values().minmax() {
-        MinMaxResult::MinMax(min, max) => Some(max - min),
-        MinMaxResult::OneElement(_) => Some(0),
-        MinMaxResult::NoElements => None,
+impl fmt::Display for PairCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Pairs that were split leave zero counts behind
+        let pairs = self
+            .pairs
+            .iter()
+            .filter(|(_, count)| **count != BigUint::default());
+        for ((a, b), count) in pairs.sorted() {
+            writeln!(f, "{}{}: {}", a, b, count)?;
+        }
+        for (c, count) in self.elements.iter().sorted() {
+            writeln!(f, "{}: {}", c, count)?;
+        }
+        Ok(())
     }
 }
 
@@ -79,7 +168,7 @@ fn simulate(template: &Polymer, insertion_rules: &InsertionRules, steps: usize)
 struct Polymer(Vec<char>);
 
 impl FromStr for Polymer {
-    type Err = AOCError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Polymer(s.chars().collect()))
@@ -100,23 +189,21 @@ struct InsertionRule {
 }
 
 impl TryFrom<String> for InsertionRule {
-    type Error = AOCError;
+    type Error = ParseError;
 
     fn try_from(s: String) -> Result<Self, Self::Error> {
-        if let Some((pair_str, insertion_str)) = s.split(" -> ").next_tuple() {
-            let pair = if let Some(pair) = pair_str.chars().next_tuple() {
-                pair
-            } else {
-                return Err(AOCError::ParseError);
-            };
-            let insertion = if let Some(c) = insertion_str.chars().next() {
-                c
-            } else {
-                return Err(AOCError::ParseError);
-            };
-            return Ok(Self { pair, insertion });
-        }
-        Err(AOCError::ParseError)
+        let (pair_str, insertion_str) = s
+            .split_once(" -> ")
+            .ok_or_else(|| ParseError::new(&s, s.chars().count(), "\" -> \""))?;
+        let pair = pair_str
+            .chars()
+            .next_tuple()
+            .ok_or_else(|| ParseError::token(&s, pair_str, "a pair of elements"))?;
+        let insertion = insertion_str
+            .chars()
+            .next()
+            .ok_or_else(|| ParseError::new(&s, s.chars().count(), "an element"))?;
+        Ok(Self { pair, insertion })
     }
 }
 
@@ -144,25 +231,9 @@ impl Deref for InsertionRules {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert_eq, proptest};
 
-    static INPUT: &str = "NNCB
-
-CH -> B
-HH -> N
-CB -> H
-NH -> C
-HB -> C
-HC -> B
-HN -> C
-NN -> C
-BH -> H
-NC -> B
-NB -> B
-BN -> B
-BB -> N
-BC -> B
-CC -> N
-CN -> C";
+    static INPUT: &str = include_str!("../../fixtures/day_14/1.txt");
 
     #[test]
     fn part_1() {
@@ -175,4 +246,34 @@ CN -> C";
         let runner = Day14::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(2188189693529));
     }
+
+    #[test]
+    fn beyond_usize() {
+        let runner = Day14::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
+        let difference = simulate(&runner.template, &runner.insertion_rules, 100).unwrap();
+        assert!(difference > BigUint::from(usize::MAX));
+    }
+
+    proptest! {
+        #[test]
+        fn pair_counts_match_polymer(seed: u64, size in 2..20usize, steps in 0..6usize) {
+            let input = Day14::generate(&mut Rng::new(seed), size);
+            let runner = Day14::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let mut polymer = runner.template.to_vec();
+            let mut counts = PairCounts::new(&runner.template);
+            for _ in 0..steps {
+                let mut next = vec![polymer[0]];
+                for (a, b) in polymer.iter().tuple_windows() {
+                    next.extend(runner.insertion_rules.get(&(*a, *b)));
+                    next.push(*b);
+                }
+                polymer = next;
+                counts.step(&runner.insertion_rules);
+            }
+            let pairs: BigUint = counts.pairs.values().sum();
+            let elements: BigUint = counts.elements.values().sum();
+            prop_assert_eq!(pairs, BigUint::from(polymer.len() - 1));
+            prop_assert_eq!(elements, BigUint::from(polymer.len()));
+        }
+    }
 }
@@ -1,75 +1,137 @@
-// use std::collections::BinaryHeap;
+use std::collections::BinaryHeap;
 
 use ndarray::Array2;
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day15 {
-    grid: Array2<usize>,
+    /// Risk levels, `None` for impassable cells
+    grid: Array2<Option<u8>>,
+    skipped: usize,
+    /// Times the grid is tiled in each direction for part 2
+    tiles: usize,
 }
 
 impl Day for Day15 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
+        Self::new_with_mode(input, ParseMode::Lenient)
+    }
+
+    fn new_with_mode(
+        input: impl Iterator<Item = String>,
+        mode: ParseMode,
+    ) -> Result<Self, AOCError> {
         let input: Vec<_> = input.collect();
-        let rows = input.len();
-        let cols = input.get(0).map(|line| line.len()).unwrap_or(0);
-        let mut grid = Array2::from_elem((rows, cols), usize::MAX);
-        for (i, line) in input.iter().enumerate() {
-            for (j, c) in line.chars().take(cols).enumerate() {
-                grid[[i, j]] = c
-                    .to_digit(10)
-                    .map(|d| d.try_into().ok())
-                    .flatten()
-                    .unwrap_or(usize::MAX);
+        let cols = input.first().map(|line| line.chars().count()).unwrap_or(0);
+        let (rows, skipped) = parse_digit_grid(&input, 10, "risk level", mode)?;
+        // Lenient mode makes missing risk levels and any other character impassable
+        let mut grid = Array2::from_elem((rows.len(), cols), None);
+        for (i, row) in rows.iter().enumerate() {
+            for (j, &risk) in row.iter().enumerate() {
+                grid[[i, j]] = risk;
             }
         }
-        Ok(Day15 { grid })
+        Ok(Day15 {
+            grid,
+            skipped,
+            tiles: 5,
+        })
+    }
+
+    fn skipped(&self) -> usize {
+        self.skipped
     }
 
     fn part_1(&self) -> Answer {
-        shortest_path(
-            &self.grid,
-            (0, 0),
-            (self.grid.nrows() - 1, self.grid.ncols() - 1),
-        )
-        .into()
+        shortest_path(&self.grid, MinBucketHeap::new()).into()
     }
 
     fn part_2(&self) -> Answer {
-        // Generate larger grid
-        let mut large_grid = Array2::zeros((5 * self.grid.nrows(), 5 * self.grid.ncols()));
-        for y_tile in 0..5 {
-            for x_tile in 0..5 {
+        shortest_path(&self.large_grid(), MinBucketHeap::new()).into()
+    }
+
+    fn algorithms(_part: Part) -> &'static [&'static str] {
+        &["bucket_heap", "binary_heap"]
+    }
+
+    fn run_algorithm(&self, part: Part, name: &str, _ctx: &PartContext) -> Option<Answer> {
+        match name {
+            "bucket_heap" => Some(self.part(part)),
+            "binary_heap" => {
+                let large_grid;
+                let grid = match part {
+                    Part::One => &self.grid,
+                    Part::Two => {
+                        large_grid = self.large_grid();
+                        &large_grid
+                    }
+                };
+                Some(shortest_path(grid, BinaryHeap::with_capacity(grid.len())).into())
+            }
+            _ => None,
+        }
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, usize)> {
+        vec![("tiles", self.tiles)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: usize) -> bool {
+        match name {
+            "tiles" => self.tiles = value,
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Day15 {
+    // The grid tiled in each direction, with risk increasing away from the original
+    fn large_grid(&self) -> Array2<Option<u8>> {
+        let shape = (
+            self.tiles * self.grid.nrows(),
+            self.tiles * self.grid.ncols(),
+        );
+        let mut large_grid = Array2::from_elem(shape, None);
+        for y_tile in 0..self.tiles {
+            for x_tile in 0..self.tiles {
                 for i in 0..self.grid.nrows() {
                     for j in 0..self.grid.ncols() {
-                        let factor = y_tile + x_tile;
+                        let factor = (y_tile + x_tile) % 9;
                         let large_grid_y = y_tile * self.grid.nrows() + i;
                         let large_grid_x = x_tile * self.grid.ncols() + j;
                         large_grid[[large_grid_y, large_grid_x]] =
-                            (self.grid[[i, j]] + factor - 1) % 9 + 1;
+                            self.grid[[i, j]].map(|risk| (risk + factor as u8 + 8) % 9 + 1);
                     }
                 }
             }
         }
 
-        shortest_path(
-            &large_grid,
-            (0, 0),
-            (large_grid.nrows() - 1, large_grid.ncols() - 1),
-        )
-        .into()
+        large_grid
+    }
+}
+
+impl Generate for Day15 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let side = size.max(1);
+        (0..side)
+            .map(|_| {
+                (0..side)
+                    .map(|_| rng.range(1..10).to_string())
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
     }
 }
 
-fn shortest_path(
-    grid: &Array2<usize>,
-    start: (usize, usize),
-    end: (usize, usize),
-) -> Option<usize> {
+// Lowest total risk from the top left to the bottom right of the grid
+fn shortest_path(grid: &Array2<Option<u8>>, mut heap: impl MinHeap<State>) -> Option<usize> {
     // Dijkstra's algorithm
+    let start = (0, 0);
+    let end = (grid.nrows().checked_sub(1)?, grid.ncols().checked_sub(1)?);
     let mut dist = Array2::from_elem(grid.dim(), usize::MAX);
-    let mut heap = MinBucketHeap::new();
-    // let mut heap = BinaryHeap::with_capacity(grid.nrows() * grid.ncols());
     let mut next_positions = Vec::with_capacity(4);
 
     dist[start] = 0;
@@ -103,10 +165,14 @@ fn shortest_path(
             next_positions.push((position.0, position.1 + 1));
         }
 
-        // Try to find a shorter path
+        // Try to find a shorter path, around impassable cells
         for &next_position in &next_positions {
+            let risk = match grid[next_position] {
+                Some(risk) => risk,
+                None => continue,
+            };
             let next = State {
-                cost: cost + grid[next_position],
+                cost: cost + risk as usize,
                 position: next_position,
             };
 
@@ -121,6 +187,21 @@ fn shortest_path(
     None
 }
 
+trait MinHeap<T> {
+    fn push(&mut self, t: T);
+    fn pop(&mut self) -> Option<T>;
+}
+
+impl<T: Ord> MinHeap<T> for BinaryHeap<T> {
+    fn push(&mut self, t: T) {
+        BinaryHeap::push(self, t);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        BinaryHeap::pop(self)
+    }
+}
+
 struct MinBucketHeap<T: IntegerPriority> {
     data: Vec<Vec<T>>,
     idx: Option<usize>,
@@ -135,7 +216,9 @@ impl<T: IntegerPriority> MinBucketHeap<T> {
             size: 0,
         }
     }
+}
 
+impl<T: IntegerPriority> MinHeap<T> for MinBucketHeap<T> {
     fn push(&mut self, t: T) {
         let priority = t.priority();
         if priority >= self.data.len() {
@@ -174,21 +257,21 @@ struct State {
     position: (usize, usize),
 }
 
-// impl Ord for State {
-    // fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // // Reverse ordering for min heap
-        // other
-            // .cost
-            // .cmp(&self.cost)
-            // .then_with(|| self.position.cmp(&other.position))
-    // }
-// }
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reverse ordering for min heap
+        other
+            .cost
+            .cmp(&self.cost)
+            .then_with(|| self.position.cmp(&other.position))
+    }
+}
 
-// impl PartialOrd for State {
-    // fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        // Some(self.cmp(other))
-    // }
-// }
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
 trait IntegerPriority {
     fn priority(&self) -> usize;
@@ -203,17 +286,9 @@ impl IntegerPriority for State {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert_eq, proptest};
 
-    static INPUT: &str = "1163751742
-1381373672
-2136511328
-3694931569
-7463417111
-1319128137
-1359912421
-3125421639
-1293138521
-2311944581";
+    static INPUT: &str = include_str!("../../fixtures/day_15/1.txt");
 
     #[test]
     fn part_1() {
@@ -226,4 +301,56 @@ mod test {
         let runner = Day15::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(315));
     }
+
+    #[test]
+    fn tiles() {
+        let mut runner = Day15::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
+        assert!(runner.set_parameter("tiles", 1));
+        assert_eq!(runner.part_2(), Answer::Integer(40));
+        assert!(!runner.set_parameter("steps", 1));
+    }
+
+    #[test]
+    fn binary_heap() {
+        let runner = Day15::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
+        let ctx = PartContext::default();
+        let answer = |part| runner.run_algorithm(part, "binary_heap", &ctx).unwrap();
+        assert_eq!(answer(Part::One), Answer::Integer(40));
+        assert_eq!(answer(Part::Two), Answer::Integer(315));
+    }
+
+    #[test]
+    fn strict_mode() {
+        let input = INPUT.lines().map(|s| s.replace("1381373672", "138137367"));
+        let runner = Day15::new(input.clone()).unwrap();
+        assert_eq!(runner.skipped(), 1);
+        match Day15::new_with_mode(input, ParseMode::Strict) {
+            Err(AOCError::ParseError(e)) => assert_eq!((e.line, e.column), (2, 10)),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn impassable_cells() {
+        let runner = Day15::new(["19", "1x"].iter().map(|s| s.to_string())).unwrap();
+        assert_eq!(runner.skipped(), 1);
+        assert_eq!(runner.part_1(), Answer::None);
+        let runner = Day15::new(["119", "1x1", "111"].iter().map(|s| s.to_string())).unwrap();
+        assert_eq!(runner.part_1(), Answer::Integer(4));
+    }
+
+    proptest! {
+        #[test]
+        fn heaps_agree(seed: u64, size in 1..16usize) {
+            let input = Day15::generate(&mut Rng::new(seed), size);
+            let runner = Day15::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let ctx = PartContext::default();
+            for part in Part::ALL {
+                prop_assert_eq!(
+                    runner.run_algorithm(part, "bucket_heap", &ctx),
+                    runner.run_algorithm(part, "binary_heap", &ctx)
+                );
+            }
+        }
+    }
 }
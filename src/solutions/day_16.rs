@@ -1,5 +1,8 @@
+use std::fmt::Write;
+
 use bitvec::prelude::*;
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day16 {
@@ -8,11 +11,15 @@ pub struct Day16 {
 
 impl Day for Day16 {
     fn new(mut input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let input = input.next().ok_or(AOCError::NoInput)?;
+        let input = input
+            .next()
+            .ok_or_else(|| ParseError::end_of_input("a hexadecimal transmission").line(0))?;
         let mut bits: BitVec = BitVec::with_capacity(4 * input.len());
-        for c in input.chars() {
+        for (j, c) in input.chars().enumerate() {
             let mut bit = 4;
-            let digit = c.to_digit(16).ok_or(AOCError::ParseError)?;
+            let digit = c
+                .to_digit(16)
+                .ok_or_else(|| ParseError::new(&input, j, "a hexadecimal digit").line(0))?;
             bits.resize_with(4 + bits.len(), || {
                 bit -= 1;
                 (1 << bit) & digit != 0
@@ -35,6 +42,127 @@ impl Day for Day16 {
         }
         Answer::None
     }
+
+    fn views(&self) -> &'static [&'static str] {
+        &["packets"]
+    }
+
+    fn inspect(&self, view: &str, _steps: usize) -> Option<String> {
+        if view != "packets" {
+            return None;
+        }
+        let mut out = String::new();
+        match parse_packet(&self.bits) {
+            Some((packet, _)) => write_packet(&mut out, &packet, 0),
+            None => out.push_str("Truncated packet\n"),
+        }
+        Some(out)
+    }
+}
+
+impl Generate for Day16 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut bits = Vec::new();
+        random_packet(rng, size.max(1), false, &mut bits);
+        // Pad to whole hexadecimal digits
+        bits.resize(bits.len().div_ceil(4) * 4, false);
+        let mut input: String = bits
+            .chunks(4)
+            .map(|nibble| {
+                let digit = nibble.iter().fold(0, |acc, &b| acc << 1 | b as u32);
+                std::char::from_digit(digit, 16)
+                    .unwrap()
+                    .to_ascii_uppercase()
+            })
+            .collect();
+        input.push('\n');
+        input
+    }
+}
+
+// Append a random packet of at most `budget` packets in total, returning the number used. Small
+// packets have values below 10, so products of them can't overflow.
+fn random_packet(rng: &mut Rng, budget: usize, small: bool, bits: &mut Vec<bool>) -> usize {
+    push_bits(bits, rng.range(0..8), 3);
+    if budget < 3 || (small && rng.chance(0.5)) {
+        push_literal(
+            bits,
+            if small {
+                rng.range(1..10)
+            } else {
+                rng.range(0..1000)
+            },
+        );
+        return 1;
+    }
+
+    // Comparisons need exactly two subpackets
+    let type_id = match small {
+        true => rng.range(5..8),
+        false => *rng.choose(&[0, 1, 2, 3, 5, 6, 7]),
+    };
+    let count = match type_id {
+        5..=7 => 2,
+        _ => rng.range(1..budget.min(4)),
+    };
+    let mut sub_bits = Vec::new();
+    let mut used = 1;
+    for i in 0..count {
+        // Leave at least one packet for each remaining subpacket
+        let share = (budget - used - (count - i - 1)) / (count - i);
+        let sub_budget = rng.range(share.div_ceil(2)..share + 1);
+        used += random_packet(rng, sub_budget, type_id == 1, &mut sub_bits);
+    }
+
+    push_bits(bits, type_id, 3);
+    if sub_bits.len() < 1 << 15 && rng.chance(0.5) {
+        bits.push(false);
+        push_bits(bits, sub_bits.len(), 15);
+    } else {
+        bits.push(true);
+        push_bits(bits, count, 11);
+    }
+    bits.extend(sub_bits);
+    used
+}
+
+fn push_literal(bits: &mut Vec<bool>, value: usize) {
+    push_bits(bits, 4, 3);
+    let groups = (usize::BITS - value.leading_zeros()).max(1).div_ceil(4) as usize;
+    for group in (0..groups).rev() {
+        bits.push(group > 0);
+        push_bits(bits, value >> (4 * group) & 0xf, 4);
+    }
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: usize, len: usize) {
+    bits.extend((0..len).rev().map(|i| value >> i & 1 == 1));
+}
+
+// Append a packet and its subpackets as an indented tree, with each operator's value
+fn write_packet(out: &mut String, packet: &Packet, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match packet {
+        Packet::Literal { version, value } => {
+            writeln!(out, "{}{} (version {})", indent, value, version).unwrap()
+        }
+        Packet::Operator {
+            version,
+            op_type,
+            subpackets,
+        } => {
+            let value = evaluate(packet).map_or("no value".into(), |v| v.to_string());
+            writeln!(
+                out,
+                "{}{:?} = {} (version {})",
+                indent, op_type, value, version
+            )
+            .unwrap();
+            for subpacket in subpackets {
+                write_packet(out, subpacket, depth + 1);
+            }
+        }
+    }
 }
 
 fn version_sum(packet: &Packet) -> usize {
@@ -60,9 +188,9 @@ fn evaluate(packet: &Packet) -> Option<usize> {
             OperatorType::Product => s.iter().map(evaluate).product(),
             OperatorType::Minimum => s.iter().map(evaluate).min().flatten(),
             OperatorType::Maximum => s.iter().map(evaluate).max().flatten(),
-            OperatorType::Greater => Some((evaluate(s.get(0)?) > evaluate(s.get(1)?)) as usize),
-            OperatorType::Less => Some((evaluate(s.get(0)?) < evaluate(s.get(1)?)) as usize),
-            OperatorType::Equal => Some((evaluate(s.get(0)?) == evaluate(s.get(1)?)) as usize),
+            OperatorType::Greater => Some((evaluate(s.first()?) > evaluate(s.get(1)?)) as usize),
+            OperatorType::Less => Some((evaluate(s.first()?) < evaluate(s.get(1)?)) as usize),
+            OperatorType::Equal => Some((evaluate(s.first()?) == evaluate(s.get(1)?)) as usize),
         },
     }
 }
@@ -236,6 +364,7 @@ enum OperatorType {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
     #[test]
     fn len_type_0() {
@@ -298,4 +427,16 @@ mod test {
             Day16::new("9C0141080250320F1802104A08".lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(1));
     }
+
+    proptest! {
+        #[test]
+        fn generated_packets_parse(seed: u64, size in 1..500usize) {
+            let input = Day16::generate(&mut Rng::new(seed), size);
+            let runner = Day16::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let (packet, rest) = parse_packet(&runner.bits).unwrap();
+            // Only the padding to whole hexadecimal digits is left
+            prop_assert!(rest.len() < 4 && rest.not_any());
+            prop_assert!(evaluate(&packet).is_some());
+        }
+    }
 }
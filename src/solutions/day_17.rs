@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 
 use regex::Regex;
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day17 {
@@ -10,42 +11,82 @@ pub struct Day17 {
 
 impl Day for Day17 {
     fn new(mut input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
-        let line = input.next().ok_or(AOCError::NoInput)?;
+        const EXPECTED: &str = "\"target area: x=A..B, y=C..D\"";
+
+        let line = input
+            .next()
+            .ok_or_else(|| ParseError::end_of_input(EXPECTED).line(0))?;
         let re =
             Regex::new(r"target area: x=(\-?\d+)\.\.(\-?\d+), y=(\-?\d+)\.\.(\-?\d+)").unwrap();
-        let caps = re.captures(&line).ok_or(AOCError::ParseError)?;
-        let values: Vec<_> = caps
+        let caps = re
+            .captures(&line)
+            .ok_or_else(|| ParseError::new(&line, 0, EXPECTED).line(0))?;
+        let tokens: Vec<_> = caps.iter().skip(1).flatten().map(|m| m.as_str()).collect();
+        let values: Vec<isize> = tokens
             .iter()
-            .skip(1)
-            .map(|cap| {
-                let s = cap.ok_or(AOCError::ParseError)?.as_str();
-                s.parse::<isize>()
-                    .map_err(|e| AOCError::ParseIntError(e, s.into()))
-            })
+            .map(|s| parse_token(&line, s, "an integer").map_err(|e| e.line(0)))
             .collect::<Result<_, _>>()?;
         if let [x1, x2, y1, y2] = values[..] {
-            if x1 > x2 || y1 > y2 {
-                return Err(AOCError::ParseError);
+            if x1 > x2 {
+                let e = ParseError::token(&line, tokens[1], "an x range end of at least its start");
+                return Err(e.line(0).into());
+            }
+            if y1 > y2 {
+                let e = ParseError::token(&line, tokens[3], "a y range end of at least its start");
+                return Err(e.line(0).into());
             }
             Ok(Self {
                 target: Target::new((x1, x2), (y1, y2)),
             })
         } else {
-            Err(AOCError::ParseError)
+            Err(ParseError::new(&line, 0, EXPECTED).line(0).into())
         }
     }
 
     fn part_1(&self) -> Answer {
-        // Answer::Integer(max_y(&self.target))
-        brute_force(&self.target, EndCondition::MaxY).into()
+        self.run_part(Part::One, &PartContext::default())
     }
 
     fn part_2(&self) -> Answer {
-        brute_force(&self.target, EndCondition::NumTrajectories).into()
+        self.run_part(Part::Two, &PartContext::default())
+    }
+
+    fn run_part(&self, part: Part, ctx: &PartContext) -> Answer {
+        let end = match part {
+            Part::One => EndCondition::MaxY,
+            Part::Two => EndCondition::NumTrajectories,
+        };
+        brute_force(&self.target, end, ctx).into()
+    }
+
+    fn algorithms(part: Part) -> &'static [&'static str] {
+        match part {
+            Part::One => &["brute_force", "naive"],
+            Part::Two => &["brute_force"],
+        }
+    }
+
+    fn run_algorithm(&self, part: Part, name: &str, ctx: &PartContext) -> Option<Answer> {
+        match (part, name) {
+            (_, "brute_force") => Some(self.run_part(part, ctx)),
+            (Part::One, "naive") => Some(max_y_naive(&self.target).into()),
+            _ => None,
+        }
+    }
+}
+
+impl Generate for Day17 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        // A target below and right of the probe, further away for larger sizes
+        let size = size.max(5) as isize;
+        let x1 = rng.range_signed(size..2 * size);
+        let x2 = x1 + rng.range_signed(1..size / 2 + 1);
+        let y2 = -rng.range_signed(size / 2..size);
+        let y1 = y2 - rng.range_signed(1..size / 2 + 1);
+        format!("target area: x={}..{}, y={}..{}\n", x1, x2, y1, y2)
     }
 }
 
-#[allow(dead_code)]
 fn max_y_naive(target: &Target) -> Option<usize> {
     // Assume that target is below y-axis and if the final y value is on target, then it is always
     // possible to reach to target
@@ -58,32 +99,45 @@ fn max_y_naive(target: &Target) -> Option<usize> {
     }
 }
 
-fn brute_force(target: &Target, end: EndCondition) -> Option<usize> {
+fn brute_force(
+    target: &Target,
+    end: EndCondition,
+    ctx: &PartContext,
+) -> Result<Option<usize>, Cancelled> {
     // Assume that target is below y axis
     if target.y.0 > 0 || target.y.1 > 0 {
-        None
+        Ok(None)
     } else {
         let x_target = Target::new(target.x, (isize::MIN, isize::MAX));
-        let x_velocities: Vec<_> = (target.x.0.min(0)..=target.x.1.max(0))
-            .filter(|xv| simulate(&x_target, (*xv, 0), SimulateAxis::X))
-            .collect();
+        let mut x_velocities = Vec::new();
+        for xv in target.x.0.min(0)..=target.x.1.max(0) {
+            // Wide targets make this quadratic, so stop when cancelled
+            ctx.check()?;
+            if simulate(&x_target, (xv, 0), SimulateAxis::X) {
+                x_velocities.push(xv);
+            }
+        }
 
         let y_target = Target::new((isize::MIN, isize::MAX), target.y);
-        let y_velocities = ((target.y.0)..=(-target.y.0))
+        let y_velocities: Vec<_> = ((target.y.0)..=(-target.y.0))
             .rev()
-            .filter(|yv| simulate(&y_target, (0, *yv), SimulateAxis::Y));
+            .filter(|yv| simulate(&y_target, (0, *yv), SimulateAxis::Y))
+            .collect();
 
         let mut count = 0;
-        for y_velocity in y_velocities {
+        for (i, &y_velocity) in y_velocities.iter().enumerate() {
+            ctx.progress(i, Some(y_velocities.len()));
+
             for &x_velocity in &x_velocities {
+                ctx.check()?;
                 if simulate(target, (x_velocity, y_velocity), SimulateAxis::Both) {
                     match end {
                         EndCondition::MaxY => {
                             if y_velocity <= 0 {
-                                return Some(0);
+                                return Ok(Some(0));
                             }
                             let temp = y_velocity as usize;
-                            return Some(temp * (temp + 1) / 2);
+                            return Ok(Some(temp * (temp + 1) / 2));
                         }
                         EndCondition::NumTrajectories => {
                             count += 1;
@@ -94,8 +148,8 @@ fn brute_force(target: &Target, end: EndCondition) -> Option<usize> {
         }
 
         match end {
-            EndCondition::NumTrajectories => Some(count),
-            EndCondition::MaxY => None,
+            EndCondition::NumTrajectories => Ok(Some(count)),
+            EndCondition::MaxY => Ok(None),
         }
     }
 }
@@ -159,15 +213,16 @@ enum EndCondition {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
-    static INPUT: &str = "target area: x=20..30, y=-10..-5";
+    static INPUT: &str = include_str!("../../fixtures/day_17/1.txt");
 
     #[test]
     fn part_1() {
         let runner = Day17::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_1(), Answer::Integer(45));
 
-        let input_2 = "target area: x=34..35, y=-8..-6";
+        let input_2 = include_str!("../../fixtures/day_17/2.txt");
         let runner = Day17::new(input_2.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_1(), Answer::Integer(3));
     }
@@ -177,4 +232,26 @@ mod test {
         let runner = Day17::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(112));
     }
+
+    #[test]
+    fn naive() {
+        let runner = Day17::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
+        let ctx = PartContext::default();
+        assert_eq!(
+            runner.run_algorithm(Part::One, "naive", &ctx),
+            Some(Answer::Integer(45))
+        );
+        assert!(runner.run_algorithm(Part::Two, "naive", &ctx).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn naive_height_is_an_upper_bound(seed: u64, size in 5..40usize) {
+            let input = Day17::generate(&mut Rng::new(seed), size);
+            let runner = Day17::new(input.lines().map(|s| s.to_owned())).unwrap();
+            let ctx = PartContext::default();
+            let height = brute_force(&runner.target, EndCondition::MaxY, &ctx).unwrap();
+            prop_assert!(height <= max_y_naive(&runner.target));
+        }
+    }
 }
@@ -9,6 +9,7 @@ use nom::combinator::{map, map_res};
 use nom::sequence::{preceded, separated_pair, terminated};
 use nom::IResult;
 
+use crate::generate::{Generate, Rng};
 use crate::prelude::*;
 
 pub struct Day18 {
@@ -18,6 +19,12 @@ pub struct Day18 {
 impl Day for Day18 {
     fn new(input: impl Iterator<Item = String>) -> Result<Self, AOCError> {
         let input: Vec<_> = input.collect();
+
+        // Numbers are parsed again by each part, since they can't be shared between threads
+        for (i, line) in input.iter().enumerate() {
+            SnailfishNumber::from_str(line).map_err(|e| e.line(i))?;
+        }
+
         Ok(Self { input })
     }
 
@@ -67,6 +74,27 @@ impl Day for Day18 {
     }
 }
 
+impl Generate for Day18 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| format!("[{},{}]\n", random_element(rng, 1), random_element(rng, 1)))
+            .collect()
+    }
+}
+
+// A regular number or a pair nested `depth` pairs deep, already reduced
+fn random_element(rng: &mut Rng, depth: usize) -> String {
+    if depth == 4 || rng.chance(0.4) {
+        rng.range(0..10).to_string()
+    } else {
+        let (left, right) = (
+            random_element(rng, depth + 1),
+            random_element(rng, depth + 1),
+        );
+        format!("[{},{}]", left, right)
+    }
+}
+
 fn add(num_1: Rc<SnailfishNumber>, num_2: Rc<SnailfishNumber>) -> Rc<SnailfishNumber> {
     let root = Rc::new(SnailfishNumber {
         sn_type: RefCell::new(SnailfishNumberType::Pair(RefCell::new([
@@ -140,7 +168,7 @@ fn find_explode(num: Rc<SnailfishNumber>, depth: usize) -> Option<Rc<SnailfishNu
         SnailfishNumberType::Regular(_) => return None,
         SnailfishNumberType::Pair(p) => {
             let p = p.borrow();
-            if let left @ Some(_) = find_explode(p.get(0).unwrap().clone(), depth + 1) {
+            if let left @ Some(_) = find_explode(p.first().unwrap().clone(), depth + 1) {
                 return left;
             }
             if let right @ Some(_) = find_explode(p.get(1).unwrap().clone(), depth + 1) {
@@ -193,7 +221,7 @@ fn split(root: Rc<SnailfishNumber>) -> Option<()> {
         SnailfishNumberType::Regular(v) => {
             let v = *v.borrow();
             let left = v / 2;
-            let right = (v + 1) / 2;
+            let right = v.div_ceil(2);
             (left, right)
         }
     };
@@ -219,7 +247,7 @@ fn find_split(num: Rc<SnailfishNumber>) -> Option<Rc<SnailfishNumber>> {
         SnailfishNumberType::Regular(v) if *v.borrow() >= 10 => Some(num.clone()),
         SnailfishNumberType::Pair(p) => {
             let p = p.borrow();
-            if let left @ Some(_) = find_split(p.get(0).unwrap().clone()) {
+            if let left @ Some(_) = find_split(p.first().unwrap().clone()) {
                 return left;
             }
             if let right @ Some(_) = find_split(p.get(1).unwrap().clone()) {
@@ -271,8 +299,18 @@ impl PartialEq for SnailfishNumber {
 }
 
 impl SnailfishNumber {
-    fn from_str(s: &str) -> Result<Rc<Self>, AOCError> {
-        let num = Rc::new(snailfish_number(s).map_err(|_| AOCError::ParseError)?.1);
+    fn from_str(s: &str) -> Result<Rc<Self>, ParseError> {
+        let (rest, num) = snailfish_number(s).map_err(|e| {
+            let offset = match e {
+                nom::Err::Error(e) | nom::Err::Failure(e) => s.len() - e.input.len(),
+                nom::Err::Incomplete(_) => s.len(),
+            };
+            ParseError::new(s, s[..offset].chars().count(), "a snailfish number")
+        })?;
+        if !rest.is_empty() {
+            return Err(ParseError::token(s, rest, "end of line"));
+        }
+        let num = Rc::new(num);
 
         // Update parent pointers
         match *num.sn_type.borrow() {
@@ -287,7 +325,7 @@ impl SnailfishNumber {
             SnailfishNumberType::Regular(v) => *v.borrow(),
             SnailfishNumberType::Pair(p) => {
                 let p = p.borrow();
-                3 * p.get(0).unwrap().magnitude() + 2 * p.get(1).unwrap().magnitude()
+                3 * p.first().unwrap().magnitude() + 2 * p.get(1).unwrap().magnitude()
             }
         }
     }
@@ -335,6 +373,7 @@ fn snailfish_number(input: &str) -> IResult<&str, SnailfishNumber> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop_assert, proptest};
 
     #[test]
     fn explode_1() {
@@ -412,16 +451,7 @@ mod test {
         assert_eq!(num.magnitude(), 1384);
     }
 
-    static INPUT: &str = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
-[[[5,[2,8]],4],[5,[[9,9],0]]]
-[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
-[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
-[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
-[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
-[[[[5,4],[7,7]],8],[[8,3],8]]
-[[9,3],[[9,9],[6,[4,9]]]]
-[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
-[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+    static INPUT: &str = include_str!("../../fixtures/day_18/1.txt");
 
     #[test]
     fn part_1() {
@@ -434,4 +464,16 @@ mod test {
         let runner = Day18::new(INPUT.lines().map(|s| s.to_owned())).unwrap();
         assert_eq!(runner.part_2(), Answer::Integer(3993));
     }
+
+    proptest! {
+        #[test]
+        fn sums_are_reduced(seed: u64) {
+            let input = Day18::generate(&mut Rng::new(seed), 2);
+            let mut numbers = input.lines().map(|line| SnailfishNumber::from_str(line).unwrap());
+            let (num_1, num_2) = (numbers.next().unwrap(), numbers.next().unwrap());
+            let sum = super::add(num_1, num_2);
+            prop_assert!(find_explode(sum.clone(), 0).is_none());
+            prop_assert!(find_split(sum).is_none());
+        }
+    }
 }
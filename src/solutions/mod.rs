@@ -1,95 +1,344 @@
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use rayon::prelude::*;
+
+use crate::generate::{Generate, Rng};
+use crate::isolate;
 use crate::prelude::*;
 
-use self::day_01::Day01;
-use self::day_02::Day02;
-use self::day_03::Day03;
-use self::day_04::Day04;
-use self::day_05::Day05;
-use self::day_06::Day06;
-use self::day_07::Day07;
-use self::day_08::Day08;
-use self::day_09::Day09;
-use self::day_10::Day10;
-use self::day_11::Day11;
-use self::day_12::Day12;
-use self::day_13::Day13;
-use self::day_14::Day14;
-use self::day_15::Day15;
-use self::day_16::Day16;
-use self::day_17::Day17;
-use self::day_18::Day18;
-
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_17;
-mod day_18;
-
-pub fn dispatch(day: DayNum) -> Result<BTreeMap<usize, Vec<Answer>>, AOCError> {
-    let mut ret = BTreeMap::new();
+// Day modules and the `DAYS` registry, generated by build.rs from the `day_NN.rs` files in this
+// directory. Each file must define a `DayNN` struct implementing `Day` and `Generate`.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+type NewRunner = fn(&mut dyn Iterator<Item = String>, ParseMode) -> Result<Box<dyn Day>, AOCError>;
+
+struct DayEntry {
+    day: usize,
+    new: NewRunner,
+    algorithms: fn(Part) -> &'static [&'static str],
+    generate: fn(&mut Rng, usize) -> String,
+}
+
+fn new_runner<D: Day + 'static>(
+    input: &mut dyn Iterator<Item = String>,
+    mode: ParseMode,
+) -> Result<Box<dyn Day>, AOCError> {
+    Ok(Box::new(D::new_with_mode(input, mode)?))
+}
+
+/// All implemented days, in ascending order
+pub fn days() -> impl Iterator<Item = usize> {
+    DAYS.iter().map(|entry| entry.day)
+}
+
+pub fn dispatch(day: DayNum, options: &RunOptions) -> Result<BTreeMap<usize, Solution>, AOCError> {
     match day {
-        DayNum::One(d, i) => {
-            ret.insert(d, run(get_runner(d, i)?)?);
-        }
-        DayNum::All => {
-            for d in 1..=25 {
-                let input_path = format!("input/day_{:02}.txt", d);
-                match get_runner(d, input_path) {
-                    Ok(r) => ret.insert(d, run(r)?),
-                    Err(_) => continue,
-                };
+        DayNum::One(d, i, part) => Ok(BTreeMap::from([(d, solve(d, &i, &parts(part), options)?)])),
+        // Only days that were solved, `solve_all` reports what happened to the others
+        DayNum::All(part) => Ok(solve_all(part, options)
+            .into_iter()
+            .filter_map(|(d, outcome)| match outcome {
+                DayOutcome::Solved(s) => Some((d, s)),
+                DayOutcome::NoInput(_) | DayOutcome::Failed(_) => None,
+            })
+            .collect()),
+    }
+}
+
+/// Solve every day from its file in `input/`, reporting what happened to each day
+pub fn solve_all(part: Option<Part>, options: &RunOptions) -> BTreeMap<usize, DayOutcome> {
+    let parts = parts(part);
+    let solve_day = |d| {
+        let path = PathBuf::from(format!("input/day_{:02}.txt", d));
+        let outcome = if !path.exists() {
+            DayOutcome::NoInput(path)
+        } else {
+            match solve(d, &InputSource::File(path.into()), &parts, options) {
+                Ok(s) => DayOutcome::Solved(s),
+                Err(e) => DayOutcome::Failed(e),
+            }
+        };
+        (d, outcome)
+    };
+    if options.parallel {
+        DAYS.par_iter().map(|e| solve_day(e.day)).collect()
+    } else {
+        days().map(solve_day).collect()
+    }
+}
+
+/// Files in a directory, or the files matching a glob pattern, in sorted order
+pub fn input_files(pattern: &str) -> Result<Vec<PathBuf>, AOCError> {
+    let mut files: Vec<_> = if Path::new(pattern).is_dir() {
+        fs::read_dir(pattern)
+            .map_err(AOCError::BadInputFile)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()
+            .map_err(AOCError::BadInputFile)?
+    } else {
+        glob::glob(pattern)
+            .map_err(|e| AOCError::BadPattern(pattern.into(), e.to_string()))?
+            .collect::<Result<_, _>>()
+            .map_err(|e| AOCError::BadInputFile(e.into()))?
+    };
+    files.retain(|path| path.is_file());
+    files.sort();
+    match files.is_empty() {
+        true => Err(AOCError::NoInputs(pattern.into())),
+        false => Ok(files),
+    }
+}
+
+/// Solve a day against each input file, with one result per file
+pub fn solve_files(
+    day: usize,
+    files: &[PathBuf],
+    parts: &[Part],
+    options: &RunOptions,
+) -> Result<Vec<Result<Solution, AOCError>>, AOCError> {
+    find_entry(day)?;
+    let solve_file = |path: &PathBuf| solve(day, &InputSource::File(path.into()), parts, options);
+    Ok(if options.parallel {
+        files.par_iter().map(solve_file).collect()
+    } else {
+        files.iter().map(solve_file).collect()
+    })
+}
+
+/// Names of the algorithms that can solve a part of a day, the first is the default
+pub fn algorithms(day: usize, part: Part) -> Result<&'static [&'static str], AOCError> {
+    Ok((find_entry(day)?.algorithms)(part))
+}
+
+/// Check that a day exists and has the named algorithm for each part, before spending any time
+/// on its input
+pub fn check_day(day: usize, parts: &[Part], algorithm: Option<&str>) -> Result<(), AOCError> {
+    let entry = find_entry(day)?;
+    if let Some(name) = algorithm {
+        for &part in parts {
+            let available = (entry.algorithms)(part);
+            if !available.contains(&name) {
+                return Err(AOCError::UnknownAlgorithm(name.into(), part, available));
             }
         }
     }
+    Ok(())
+}
 
-    Ok(ret)
+/// Generate random puzzle input for a day
+pub fn generate_input(day: usize, rng: &mut Rng, size: usize) -> Result<String, AOCError> {
+    Ok((find_entry(day)?.generate)(rng, size))
 }
 
-pub fn get_runner(
+pub fn get_runner(day: usize, input_path: impl AsRef<Path>) -> Result<Box<dyn Day>, AOCError> {
+    let entry = find_entry(day)?;
+    let mut input = read_input_lines(&input_path)?;
+    let runner = (entry.new)(&mut input, ParseMode::default());
+    input.finish()?;
+    runner.map_err(|e| e.with_path(input_path))
+}
+
+/// Create a runner for a day from puzzle input in any buffered reader
+pub fn get_runner_from_reader(day: usize, input: impl BufRead) -> Result<Box<dyn Day>, AOCError> {
+    let entry = find_entry(day)?;
+    let mut input = read_lines(input);
+    let runner = (entry.new)(&mut input, ParseMode::default());
+    input.finish()?;
+    runner
+}
+
+/// Solve both parts of a day from puzzle input held in memory
+pub fn solve_str(day: usize, input: &str, options: &RunOptions) -> Result<Solution, AOCError> {
+    solve_reader(day, input.as_bytes(), options)
+}
+
+/// Read, parse and solve both parts of a day from any buffered reader, timing each phase
+pub fn solve_reader(
     day: usize,
-    input_path: impl AsRef<Path>,
-) -> Result<Box<dyn Day>, AOCError> {
-    let input = read_input_lines(input_path)?;
-    let runner: Box<dyn Day> = match day {
-        1 => Box::new(Day01::new(input)?),
-        2 => Box::new(Day02::new(input)?),
-        3 => Box::new(Day03::new(input)?),
-        4 => Box::new(Day04::new(input)?),
-        5 => Box::new(Day05::new(input)?),
-        6 => Box::new(Day06::new(input)?),
-        7 => Box::new(Day07::new(input)?),
-        8 => Box::new(Day08::new(input)?),
-        9 => Box::new(Day09::new(input)?),
-        10 => Box::new(Day10::new(input)?),
-        11 => Box::new(Day11::new(input)?),
-        12 => Box::new(Day12::new(input)?),
-        13 => Box::new(Day13::new(input)?),
-        14 => Box::new(Day14::new(input)?),
-        15 => Box::new(Day15::new(input)?),
-        16 => Box::new(Day16::new(input)?),
-        17 => Box::new(Day17::new(input)?),
-        18 => Box::new(Day18::new(input)?),
-        _ => return Err(AOCError::DayOutOfRange(day)),
+    input: impl BufRead,
+    options: &RunOptions,
+) -> Result<Solution, AOCError> {
+    solve_parts(day, input, &Part::ALL, options)
+}
+
+/// Read, parse and solve only the given parts of a day from any buffered reader, timing each
+/// phase
+pub fn solve_parts(
+    day: usize,
+    input: impl BufRead,
+    parts: &[Part],
+    options: &RunOptions,
+) -> Result<Solution, AOCError> {
+    check_day(day, parts, options.algorithm.as_deref())?;
+    let (runner, read_time, parse_time) = parse(day, input, options)?;
+
+    let solve = |&part| {
+        solve_part(
+            runner.as_ref(),
+            day,
+            part,
+            options.algorithm.as_deref(),
+            options,
+        )
+    };
+    let parts = if options.parallel {
+        parts.par_iter().map(solve).collect()
+    } else {
+        parts.iter().map(solve).collect()
+    };
+    Ok(Solution {
+        read_time,
+        parse_time,
+        skipped: runner.skipped(),
+        parts,
+    })
+}
+
+/// Solve the given parts of a day with every algorithm, one at a time so they're timed fairly
+pub fn crosscheck(
+    day: usize,
+    input: &InputSource,
+    parts: &[Part],
+    options: &RunOptions,
+) -> Result<Vec<CrossCheck>, AOCError> {
+    let entry = find_entry(day)?;
+    let (runner, _, _) = with_reader(input, |reader| parse(day, reader, options))?;
+    Ok(parts
+        .iter()
+        .map(|&part| CrossCheck {
+            part,
+            results: (entry.algorithms)(part)
+                .iter()
+                .map(|&name| {
+                    (
+                        name,
+                        solve_part(runner.as_ref(), day, part, Some(name), options),
+                    )
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+fn find_entry(day: usize) -> Result<&'static DayEntry, AOCError> {
+    DAYS.iter()
+        .find(|entry| entry.day == day)
+        .ok_or(AOCError::DayOutOfRange(day))
+}
+
+fn solve(
+    day: usize,
+    input: &InputSource,
+    parts: &[Part],
+    options: &RunOptions,
+) -> Result<Solution, AOCError> {
+    find_entry(day)?;
+    if let Some(isolation) = &options.isolation {
+        return isolate::solve_isolated(day, input, parts, options, isolation);
+    }
+    with_reader(input, |reader| solve_parts(day, reader, parts, options))
+}
+
+// Call `f` with a reader for the input, adding the path to errors from files
+fn with_reader<T>(
+    input: &InputSource,
+    f: impl FnOnce(&mut dyn BufRead) -> Result<T, AOCError>,
+) -> Result<T, AOCError> {
+    match input {
+        InputSource::File(path) => {
+            let file = File::open(path).map_err(AOCError::BadInputFile)?;
+            f(&mut BufReader::new(file)).map_err(|e| e.with_path(path))
+        }
+        InputSource::Stdin => f(&mut io::stdin().lock()),
+        InputSource::Text(text) => f(&mut text.as_bytes()),
+    }
+}
+
+// Parse a day's input, returning the runner with the time spent reading and parsing
+fn parse(
+    day: usize,
+    input: impl BufRead,
+    options: &RunOptions,
+) -> Result<(Box<dyn Day>, Duration, Duration), AOCError> {
+    let entry = find_entry(day)?;
+
+    // Input is read while parsing, so split the time spent between the two
+    let start = Instant::now();
+    let mut input = read_lines(input);
+    let runner = (entry.new)(&mut input, options.parse_mode);
+    let read_time = input.finish()?;
+    let runner = runner?;
+    Ok((runner, read_time, start.elapsed().saturating_sub(read_time)))
+}
+
+// Solve a part with a time limit, using the default algorithm unless one is named
+fn solve_part(
+    runner: &dyn Day,
+    day: usize,
+    part: Part,
+    algorithm: Option<&str>,
+    options: &RunOptions,
+) -> PartSolution {
+    let ctx = PartContext {
+        day,
+        part: Some(part),
+        cancel: options.cancel.child(),
+        progress: options.progress.clone(),
     };
-    Ok(runner)
+    let _watchdog = options
+        .timeout
+        .map(|timeout| Watchdog::start(timeout, ctx.cancel.clone()));
+    timed(part, || match algorithm {
+        None => runner.run_part(part, &ctx),
+        Some(name) => runner.run_algorithm(part, name, &ctx).unwrap_or_else(|| {
+            let available = algorithms(day, part).unwrap_or_default();
+            Answer::Error(Box::new(AOCError::UnknownAlgorithm(
+                name.into(),
+                part,
+                available,
+            )))
+        }),
+    })
+}
+
+// Parts to solve for an optional part selection
+fn parts(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    }
 }
 
-fn run(runner: Box<dyn Day>) -> Result<Vec<Answer>, AOCError> {
-    Ok(vec![runner.part_1(), runner.part_2()])
+// Cancels a token once its time limit passes, unless dropped first
+struct Watchdog {
+    // Dropping the sender wakes the watchdog thread early
+    _done: mpsc::Sender<()>,
+}
+
+impl Watchdog {
+    fn start(timeout: Duration, cancel: CancelToken) -> Self {
+        let (done, wait) = mpsc::channel();
+        thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = wait.recv_timeout(timeout) {
+                cancel.cancel();
+            }
+        });
+        Self { _done: done }
+    }
+}
+
+fn timed(part: Part, solve: impl FnOnce() -> Answer) -> PartSolution {
+    let start = Instant::now();
+    let answer = solve();
+    PartSolution {
+        part,
+        answer,
+        time: start.elapsed(),
+    }
 }
@@ -0,0 +1,53 @@
+//! Re-solving a day whenever its input file changes.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::prelude::*;
+
+/// Polls an input file for changes to its contents
+pub struct InputWatcher {
+    path: PathBuf,
+    // Modification time and length when the file was last read
+    stamp: Option<(SystemTime, u64)>,
+    contents: Option<Vec<u8>>,
+}
+
+impl InputWatcher {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            stamp: None,
+            contents: None,
+        }
+    }
+
+    /// New contents of the file if they changed since the last poll. A missing file hasn't
+    /// changed, so a file that is briefly removed while an editor saves it isn't solved empty.
+    pub fn poll(&mut self) -> Result<Option<&[u8]>, AOCError> {
+        let not_found = |e: &io::Error| e.kind() == io::ErrorKind::NotFound;
+
+        // Only read the file when its modification time or length changed
+        let stamp = match fs::metadata(&self.path).and_then(|m| Ok((m.modified()?, m.len()))) {
+            Ok(stamp) => stamp,
+            Err(e) if not_found(&e) => return Ok(None),
+            Err(e) => return Err(AOCError::BadInputFile(e)),
+        };
+        if self.stamp == Some(stamp) {
+            return Ok(None);
+        }
+        let contents = match fs::read(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if not_found(&e) => return Ok(None),
+            Err(e) => return Err(AOCError::BadInputFile(e)),
+        };
+        self.stamp = Some(stamp);
+        // A file saved without changes has a new modification time but nothing to solve
+        if self.contents.as_ref() == Some(&contents) {
+            return Ok(None);
+        }
+        Ok(Some(self.contents.insert(contents)))
+    }
+}
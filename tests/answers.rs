@@ -0,0 +1,16 @@
+use aoc2021::answers::*;
+use aoc2021::solutions::get_runner;
+
+fn check(day: usize, part: usize) {
+    let expected = read_answers(ANSWERS_PATH).unwrap();
+    let runner = get_runner(day, format!("input/day_{:02}.txt", day)).unwrap();
+    let answer = match part {
+        1 => runner.part_1(),
+        2 => runner.part_2(),
+        _ => panic!("Invalid part {}", part),
+    };
+    assert_eq!(answer, expected[&(day, part)]);
+}
+
+// One test per expected answer, generated by build.rs from answers.txt
+include!(concat!(env!("OUT_DIR"), "/answer_tests.rs"));
@@ -0,0 +1,127 @@
+use std::io::Cursor;
+
+use aoc2021::prelude::*;
+use aoc2021::solutions::*;
+
+static INPUT: &str = "target area: x=20..30, y=-10..-5";
+
+#[test]
+fn solve_from_str() {
+    let solution = solve_str(17, INPUT, &RunOptions::default()).unwrap();
+    assert_eq!(solution.parts[0].answer, Answer::Integer(45));
+    assert_eq!(solution.parts[1].answer, Answer::Integer(112));
+}
+
+#[test]
+fn solve_from_reader() {
+    let solution = solve_reader(17, Cursor::new(INPUT), &RunOptions::default()).unwrap();
+    assert_eq!(solution.parts[0].answer, Answer::Integer(45));
+    assert_eq!(solution.parts[1].answer, Answer::Integer(112));
+}
+
+#[test]
+fn read_error_after_valid_lines() {
+    let input: &[u8] = b"199\n200\n\xff\n";
+    let result = solve_reader(1, input, &RunOptions::default());
+    assert!(matches!(result, Err(AOCError::BadInputFile(_))));
+}
+
+#[test]
+fn parse_error_location() {
+    let result = solve_str(2, "forward 5\ndown x\n", &RunOptions::default());
+    match result {
+        Err(AOCError::ParseError(e)) => {
+            assert_eq!((e.line, e.column), (2, 6));
+            assert_eq!(e.text, "down x");
+            assert_eq!(e.found, "\"x\"");
+        }
+        _ => panic!("expected a parse error"),
+    }
+}
+
+#[test]
+fn solve_single_part() {
+    let solution = solve_parts(17, INPUT.as_bytes(), &[Part::Two], &RunOptions::default()).unwrap();
+    assert_eq!(solution.parts.len(), 1);
+    assert!(solution.part(Part::One).is_none());
+    assert_eq!(
+        solution.part(Part::Two).unwrap().answer,
+        Answer::Integer(112)
+    );
+}
+
+#[test]
+fn cancelled_parts_time_out() {
+    let options = RunOptions::default();
+    options.cancel.cancel();
+    let solution = solve_str(17, INPUT, &options).unwrap();
+    assert_eq!(solution.parts[0].answer, Answer::Timeout);
+    assert_eq!(solution.parts[1].answer, Answer::Timeout);
+}
+
+#[test]
+fn all_days_report_outcomes() {
+    // Which days have input depends on the checkout, so only check each day has an outcome
+    let outcomes = run_all(Some(Part::One), &RunOptions::default());
+    assert!(outcomes.keys().copied().eq(days()));
+    for outcome in outcomes.values() {
+        if let DayOutcome::Solved(s) = outcome {
+            assert_eq!(s.parts.len(), 1);
+        }
+    }
+}
+
+#[test]
+fn solve_with_algorithm() {
+    let options = RunOptions {
+        algorithm: Some("naive".into()),
+        ..RunOptions::default()
+    };
+    let solution = solve_parts(17, INPUT.as_bytes(), &[Part::One], &options).unwrap();
+    assert_eq!(solution.parts[0].answer, Answer::Integer(45));
+    let result = solve_str(17, INPUT, &options);
+    assert!(matches!(
+        result,
+        Err(AOCError::UnknownAlgorithm(_, Part::Two, _))
+    ));
+}
+
+#[test]
+fn crosscheck_flags_disagreements() {
+    let options = RunOptions::default();
+    let checks = crosscheck(17, &InputSource::Text(INPUT.into()), &Part::ALL, &options).unwrap();
+    assert_eq!(checks[0].results.len(), 2);
+    assert!(checks.iter().all(CrossCheck::agrees));
+
+    // No x velocity stops above this target, which the naive algorithm assumes
+    let input = InputSource::Text("target area: x=33..35, y=-27..-19".into());
+    let checks = crosscheck(17, &input, &[Part::One], &options).unwrap();
+    assert!(!checks[0].agrees());
+}
+
+#[test]
+fn solve_each_input_file() {
+    let dir = std::env::temp_dir().join(format!("aoc2021_inputs_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.txt"), INPUT).unwrap();
+    std::fs::write(dir.join("b.txt"), "target area: x=20..30").unwrap();
+    std::fs::write(dir.join("c.log"), INPUT).unwrap();
+
+    let all = input_files(dir.to_str().unwrap());
+    let txt = input_files(dir.join("*.txt").to_str().unwrap());
+    let results = txt
+        .as_ref()
+        .ok()
+        .map(|files| solve_files(17, files, &[Part::One], &RunOptions::default()).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(all.unwrap().len(), 3);
+    let txt = txt.unwrap();
+    assert_eq!(txt, [dir.join("a.txt"), dir.join("b.txt")]);
+    let results = results.unwrap();
+    assert_eq!(
+        results[0].as_ref().unwrap().parts[0].answer,
+        Answer::Integer(45)
+    );
+    assert!(matches!(results[1], Err(AOCError::ParseError(_))));
+}
@@ -0,0 +1,90 @@
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+use aoc2021::bench::*;
+use aoc2021::prelude::*;
+
+fn stats(median_ns: u64) -> Stats {
+    Stats::from_samples(&[Duration::from_nanos(median_ns)])
+}
+
+fn run(rev: &str, name: Option<&str>, median_ns: u64) -> BenchRun {
+    BenchRun {
+        rev: rev.into(),
+        name: name.map(str::to_owned),
+        timestamp: 0,
+        results: vec![PartBench {
+            day: 1,
+            part: Part::One,
+            stats: stats(median_ns),
+        }],
+    }
+}
+
+#[test]
+fn sample_statistics() {
+    let samples = [40, 10, 30, 20].map(Duration::from_nanos);
+    let stats = Stats::from_samples(&samples);
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.median, Duration::from_nanos(25));
+    assert_eq!(stats.min, Duration::from_nanos(10));
+    assert_eq!(stats.max, Duration::from_nanos(40));
+    assert_eq!(stats.mean, Duration::from_nanos(25));
+}
+
+#[test]
+fn measure_takes_min_samples() {
+    let config = TimerConfig {
+        warm_up: Duration::ZERO,
+        measure: Duration::ZERO,
+        sample_time: Duration::ZERO,
+        min_samples: 5,
+        max_samples: 5,
+    };
+    let mut runs = 0;
+    let stats = measure(&config, || runs += 1);
+    assert_eq!(stats.samples, 5);
+    // One warm-up run
+    assert_eq!(runs, 6);
+}
+
+#[test]
+fn history_round_trip() {
+    let path = env::temp_dir().join(format!("aoc2021_bench_{}.json", std::process::id()));
+    let history = vec![run("abc123", None, 100), run("def456", Some("main"), 200)];
+    write_history(&path, &history).unwrap();
+    let read = read_history(&path);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(read.unwrap(), history);
+}
+
+#[test]
+fn missing_history_is_empty() {
+    let history = read_history("does/not/exist.json").unwrap();
+    assert!(history.is_empty());
+}
+
+#[test]
+fn baseline_by_name_or_revision() {
+    let history = vec![
+        run("abc123", Some("main"), 100),
+        run("def456", None, 200),
+        run("abc789", Some("main"), 300),
+    ];
+    assert_eq!(find_baseline(&history, "main").unwrap().rev, "abc789");
+    assert_eq!(find_baseline(&history, "def").unwrap().rev, "def456");
+    assert!(find_baseline(&history, "xyz").is_none());
+}
+
+#[test]
+fn flags_regressions_over_threshold() {
+    let baseline = run("abc123", None, 100);
+    let comparisons = compare(&run("def456", None, 105), Some(&baseline), 10.0);
+    assert!(!comparisons[0].regressed);
+    let comparisons = compare(&run("def456", None, 120), Some(&baseline), 10.0);
+    assert!(comparisons[0].regressed);
+    assert!((comparisons[0].change.unwrap() - 0.2).abs() < 1e-9);
+    let comparisons = compare(&run("def456", None, 120), None, 10.0);
+    assert!(!comparisons[0].regressed && comparisons[0].baseline.is_none());
+}
@@ -0,0 +1,30 @@
+use aoc2021::examples::*;
+use aoc2021::prelude::*;
+use aoc2021::solutions::solve_str;
+
+fn check(day: usize, name: &str) {
+    let example = example(day, Some(name)).unwrap();
+    let solution = solve_str(day, example.input, &RunOptions::default()).unwrap();
+    for part_solution in &solution.parts {
+        if let Some(expected) = example.answer(part_solution.part) {
+            assert_eq!(
+                part_solution.answer, expected,
+                "part {}",
+                part_solution.part
+            );
+        }
+    }
+}
+
+#[test]
+fn first_example_by_default() {
+    assert_eq!(example(17, None).unwrap().name, "1");
+    assert_eq!(examples(16).count(), 15);
+    assert!(matches!(
+        example(17, Some("3")),
+        Err(AOCError::UnknownExample(17, _, _))
+    ));
+}
+
+// One test per example, generated by build.rs from the files in fixtures/
+include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));
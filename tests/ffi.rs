@@ -0,0 +1,58 @@
+//! Builds the C API as a shared library and runs the C smoke test against it. Only runs with
+//! `--features ffi` and needs a C compiler as `cc`.
+#![cfg(all(feature = "ffi", unix))]
+
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn c_smoke_test() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // A separate target directory, as the one running this test is locked by cargo
+    let target_dir = root.join("target").join("ffi");
+    let status = Command::new(env!("CARGO"))
+        .args([
+            "rustc",
+            "--lib",
+            "--features",
+            "ffi",
+            "--crate-type",
+            "cdylib",
+        ])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .current_dir(root)
+        .status()
+        .unwrap();
+    assert!(status.success(), "building the cdylib failed");
+
+    let lib_dir = target_dir.join("debug");
+    let smoke = target_dir.join("smoke");
+    let status = Command::new("cc")
+        .arg(root.join("tests").join("ffi").join("smoke.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .args(["-laoc2021", "-Wall", "-Werror", "-o"])
+        .arg(&smoke)
+        .status()
+        .unwrap();
+    assert!(status.success(), "compiling the smoke test failed");
+
+    // Cargo puts its own library directories on the search path, which have another build of
+    // the library, so only the rpath should be searched
+    let output = Command::new(&smoke)
+        .env_remove("LD_LIBRARY_PATH")
+        .env_remove("DYLD_LIBRARY_PATH")
+        .arg(root.join("fixtures").join("day_14").join("1.txt"))
+        .arg(root.join("fixtures").join("day_13").join("1.txt"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
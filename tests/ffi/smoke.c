@@ -0,0 +1,124 @@
+/*
+ * Smoke test for the C API, run by tests/ffi.rs with the day 14 and day 13 examples:
+ *
+ *     smoke fixtures/day_14/1.txt fixtures/day_13/1.txt
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "aoc2021.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                    \
+  do {                                                                 \
+    if (!(cond)) {                                                     \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+              #cond);                                                  \
+      failures++;                                                      \
+    }                                                                  \
+  } while (0)
+
+static uint8_t *read_file(const char *path, size_t *len) {
+  FILE *f = fopen(path, "rb");
+  if (f == NULL) {
+    perror(path);
+    exit(2);
+  }
+  fseek(f, 0, SEEK_END);
+  *len = (size_t)ftell(f);
+  fseek(f, 0, SEEK_SET);
+  uint8_t *buf = malloc(*len);
+  if (fread(buf, 1, *len, f) != *len) {
+    perror(path);
+    exit(2);
+  }
+  fclose(f);
+  return buf;
+}
+
+static Aoc2021Runner *runner_from_file(uint32_t day, const char *path) {
+  size_t len;
+  uint8_t *input = read_file(path, &len);
+  Aoc2021Runner *runner = aoc2021_runner_new(day, input, len);
+  free(input);
+  if (runner == NULL) {
+    fprintf(stderr, "%s: %s\n", path, aoc2021_last_error());
+    exit(1);
+  }
+  return runner;
+}
+
+/* Integer answers, day 14 part 2 is solved with big integers but fits in 64 bits */
+static void integers(const char *path) {
+  Aoc2021Runner *runner = runner_from_file(14, path);
+  int64_t expected[] = {1588, 2188189693529};
+  for (uint32_t part = 1; part <= 2; part++) {
+    Aoc2021Answer *answer = aoc2021_run_part(runner, part);
+    CHECK(answer != NULL);
+    CHECK(aoc2021_answer_kind(answer) == AOC2021_ANSWER_KIND_INTEGER);
+    int64_t value = 0;
+    CHECK(aoc2021_answer_integer(answer, &value));
+    CHECK(value == expected[part - 1]);
+    CHECK(strcmp(aoc2021_answer_text(answer), part == 1 ? "1588" : "2188189693529") == 0);
+    size_t len = 1;
+    CHECK(aoc2021_answer_bytes(answer, &len) == NULL && len == 0);
+    aoc2021_answer_free(answer);
+  }
+  aoc2021_runner_free(runner);
+}
+
+/* Printable answers, day 13 part 2 draws a square */
+static void printable(const char *path) {
+  Aoc2021Runner *runner = runner_from_file(13, path);
+  Aoc2021Answer *answer = aoc2021_run_part(runner, 2);
+  /* Answers outlive their runner */
+  aoc2021_runner_free(runner);
+
+  CHECK(aoc2021_answer_kind(answer) == AOC2021_ANSWER_KIND_PRINTABLE);
+  int64_t value = 0;
+  CHECK(!aoc2021_answer_integer(answer, &value));
+  size_t len = 0;
+  const uint8_t *bytes = aoc2021_answer_bytes(answer, &len);
+  const char *first_row = "\xe2\xac\x9c\xe2\xac\x9c\xe2\xac\x9c\xe2\xac\x9c\xe2\xac\x9c\n";
+  CHECK(bytes != NULL && len > strlen(first_row));
+  CHECK(memcmp(bytes, first_row, strlen(first_row)) == 0);
+  CHECK(strlen(aoc2021_answer_text(answer)) == len);
+  aoc2021_answer_free(answer);
+}
+
+static void errors(const char *path) {
+  CHECK(aoc2021_runner_new(99, NULL, 0) == NULL);
+  CHECK(strcmp(aoc2021_last_error(), "Invalid day: 99") == 0);
+
+  const char *empty = "";
+  CHECK(aoc2021_runner_new(14, (const uint8_t *)empty, 0) == NULL);
+  CHECK(strncmp(aoc2021_last_error(), "Could not parse input", 21) == 0);
+
+  Aoc2021Runner *runner = runner_from_file(14, path);
+  CHECK(aoc2021_run_part(runner, 3) == NULL);
+  CHECK(strcmp(aoc2021_last_error(), "Invalid part: \"3\"") == 0);
+  CHECK(aoc2021_run_part(NULL, 1) == NULL);
+  aoc2021_runner_free(runner);
+
+  aoc2021_runner_free(NULL);
+  aoc2021_answer_free(NULL);
+}
+
+int main(int argc, char **argv) {
+  if (argc != 3) {
+    fprintf(stderr, "usage: %s DAY_14_INPUT DAY_13_INPUT\n", argv[0]);
+    return 2;
+  }
+  integers(argv[1]);
+  printable(argv[2]);
+  errors(argv[1]);
+  if (failures > 0) {
+    fprintf(stderr, "%d check(s) failed\n", failures);
+    return 1;
+  }
+  printf("ok\n");
+  return 0;
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use aoc2021::generate::generate;
+use aoc2021::prelude::*;
+use aoc2021::solutions::*;
+
+#[test]
+fn generated_inputs_solve() {
+    // Some generated inputs have no answer within any time, such as octopi that never synchronize
+    let options = RunOptions {
+        parse_mode: ParseMode::Strict,
+        timeout: Some(Duration::from_secs(1)),
+        ..RunOptions::default()
+    };
+    for day in days() {
+        for seed in 0..3 {
+            let input = generate(day, seed, 8).unwrap();
+            let solution = solve_str(day, &input, &options)
+                .unwrap_or_else(|e| panic!("day {} seed {}: {}\n{}", day, seed, e, input));
+            for p in &solution.parts {
+                assert!(
+                    !matches!(p.answer, Answer::Error(_)),
+                    "day {} seed {} part {}: {:?}",
+                    day,
+                    seed,
+                    p.part,
+                    p.answer
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn same_seed_same_input() {
+    for day in days() {
+        assert_eq!(generate(day, 7, 20).unwrap(), generate(day, 7, 20).unwrap());
+        assert_ne!(generate(day, 7, 20).unwrap(), generate(day, 8, 20).unwrap());
+    }
+}
+
+#[test]
+fn unknown_day() {
+    assert!(matches!(
+        generate(25, 0, 10),
+        Err(AOCError::DayOutOfRange(25))
+    ));
+}
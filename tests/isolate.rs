@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use aoc2021::isolate::*;
+use aoc2021::prelude::*;
+
+fn isolated() -> RunOptions {
+    RunOptions {
+        isolation: Some(Isolation {
+            exe: PathBuf::from(env!("CARGO_BIN_EXE_aoc2021")),
+            max_memory: None,
+            max_cpu: None,
+        }),
+        ..RunOptions::default()
+    }
+}
+
+// Options that run a script from `tests/isolate` in place of the child
+fn with_exe(script: &str) -> RunOptions {
+    let exe = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("isolate")
+        .join(script);
+    RunOptions {
+        isolation: Some(Isolation {
+            exe,
+            max_memory: None,
+            max_cpu: None,
+        }),
+        ..RunOptions::default()
+    }
+}
+
+fn isolation_of(options: &RunOptions) -> &Isolation {
+    options.isolation.as_ref().unwrap()
+}
+
+#[test]
+fn isolated_solution() {
+    let input = InputSource::Text("target area: x=20..30, y=-10..-5".into());
+    let solutions = run_solutions(DayNum::One(17, input, Some(Part::Two)), &isolated()).unwrap();
+    let parts = &solutions[&17].parts;
+    assert_eq!(parts.len(), 1);
+    assert_eq!(parts[0].part, Part::Two);
+    assert_eq!(parts[0].answer, Answer::Integer(112));
+}
+
+#[test]
+#[cfg(unix)]
+fn crash_becomes_error() {
+    // Day 3 keeps every line, which needs more memory than the child may use, so allocating
+    // aborts it
+    let mut options = isolated();
+    if let Some(isolation) = &mut options.isolation {
+        isolation.max_memory = Some(16 << 20);
+    }
+    let input = InputSource::Text("0101010101\n".repeat(1 << 20));
+    let solutions = run_solutions(DayNum::One(3, input, None), &options).unwrap();
+    for part in &solutions[&3].parts {
+        assert!(matches!(&part.answer, Answer::Error(e) if e.to_string().starts_with("Crashed")));
+    }
+}
+
+#[test]
+fn long_text_input() {
+    // Longer than a single command line argument may be
+    let input = InputSource::Text("199\n200\n".repeat(50_000));
+    let solutions = run_solutions(DayNum::One(1, input, None), &isolated()).unwrap();
+    assert_eq!(solutions[&1].parts[0].answer, Answer::Integer(50_000));
+}
+
+#[test]
+fn unknown_days_and_algorithms_fail_before_spawning() {
+    // Spawning would fail, since there's no such script
+    let options = with_exe("missing.sh");
+    let input = InputSource::Text("forward 5\n".into());
+    let result = solve_isolated(99, &input, &Part::ALL, &options, isolation_of(&options));
+    assert!(matches!(result, Err(AOCError::DayOutOfRange(99))));
+    let options = RunOptions {
+        algorithm: Some("nope".into()),
+        ..options
+    };
+    let result = solve_isolated(2, &input, &Part::ALL, &options, isolation_of(&options));
+    assert!(matches!(result, Err(AOCError::UnknownAlgorithm(..))));
+}
+
+#[test]
+#[cfg(unix)]
+fn child_errors_keep_their_kind() {
+    let options = with_exe("no_input.sh");
+    let input = InputSource::Text(String::new());
+    let result = solve_isolated(2, &input, &Part::ALL, &options, isolation_of(&options));
+    assert!(matches!(result, Err(AOCError::NoInput)));
+}
+
+#[test]
+#[cfg(unix)]
+fn stuck_child_is_killed() {
+    let options = RunOptions {
+        timeout: Some(Duration::from_millis(100)),
+        ..with_exe("hang.sh")
+    };
+    let input = InputSource::Text(String::new());
+    let start = Instant::now();
+    let solution = solve_isolated(2, &input, &Part::ALL, &options, isolation_of(&options)).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(30));
+    assert!(solution.parts.iter().all(|p| p.answer == Answer::Timeout));
+}
+
+#[test]
+fn child_parse_errors_keep_their_position() {
+    let options = RunOptions {
+        parse_mode: ParseMode::Strict,
+        ..isolated()
+    };
+    let input = InputSource::Text("forward 5\ndown \"x\"\n".into());
+    match run_solutions(DayNum::One(2, input, None), &options) {
+        Err(AOCError::ParseError(e)) => {
+            assert_eq!((e.line, e.column), (2, 6));
+            assert_eq!(e.text, "down \"x\"");
+            assert_eq!(e.found, "\"\\\"x\\\"\"");
+        }
+        result => panic!("expected a parse error, got {:?}", result.map(|_| ())),
+    }
+}
@@ -0,0 +1,3 @@
+#!/bin/sh
+# Stands in for a child stuck in a day that never checks for cancellation
+exec sleep 60
@@ -0,0 +1,3 @@
+#!/bin/sh
+# Stands in for a child that reports a day has no input
+echo 'error no_input "No input"'
@@ -0,0 +1,16 @@
+use std::process::Command;
+
+use serde_json::Value;
+
+#[test]
+fn json_answers_are_numbers() {
+    let out = Command::new(env!("CARGO_BIN_EXE_aoc2021"))
+        .args(["14", "--example", "1", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let records: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(records[0]["kind"], "Integer");
+    assert_eq!(records[0]["value"], 1588);
+    assert_eq!(records[1]["value"], 2188189693529u64);
+}
@@ -0,0 +1,38 @@
+use aoc2021::generate::generate;
+use aoc2021::solutions::*;
+use proptest::prelude::*;
+
+// Parsers may reject any input, but must return an error rather than panic
+
+fn day() -> impl Strategy<Value = usize> {
+    proptest::sample::select(days().collect::<Vec<_>>())
+}
+
+proptest! {
+    #[test]
+    fn parsers_survive_arbitrary_strings(day in day(), input in "(?s).{0,300}") {
+        let _ = get_runner_from_reader(day, input.as_bytes());
+    }
+
+    #[test]
+    fn parsers_survive_likely_characters(day in day(), input in "[0-9a-zA-Z ,=>|\\[\\]()<>{}.\n-]{0,300}") {
+        let _ = get_runner_from_reader(day, input.as_bytes());
+    }
+
+    #[test]
+    fn parsers_survive_corrupted_inputs(
+        day in day(),
+        seed: u64,
+        size in 1..30usize,
+        edits in proptest::collection::vec((any::<prop::sample::Index>(), any::<char>()), 1..5),
+    ) {
+        // Replace a few characters of valid input, so parsing gets further before failing
+        let mut input: Vec<char> = generate(day, seed, size).unwrap().chars().collect();
+        for (index, c) in edits {
+            let i = index.index(input.len());
+            input[i] = c;
+        }
+        let input: String = input.into_iter().collect();
+        let _ = get_runner_from_reader(day, input.as_bytes());
+    }
+}
@@ -0,0 +1,77 @@
+use aoc2021::repl::Repl;
+
+// Output of a script of commands, one per line
+fn session(day: usize, path: &str, script: &str) -> String {
+    let mut repl = Repl::new(day, path).unwrap();
+    let mut out = Vec::new();
+    repl.run(script.as_bytes(), &mut out, false).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+// Answers printed by the session, without their times
+fn answers(out: &str) -> Vec<&str> {
+    out.lines()
+        .filter(|line| line.starts_with("Part "))
+        .map(|line| line.rsplit_once(" (").unwrap().0)
+        .collect()
+}
+
+#[test]
+fn parameters_change_answers() {
+    let out = session(
+        14,
+        "fixtures/day_14/1.txt",
+        "run\nset steps_1 2\n1\nreload\n1\nset nope 1\n",
+    );
+    assert_eq!(
+        answers(&out),
+        [
+            "Part 1: 1588",
+            "Part 2: 2188189693529",
+            "Part 1: 5",
+            "Part 1: 5"
+        ]
+    );
+    assert!(out.contains("Error: No parameter \"nope\", expected one of: steps_1, steps_2"));
+}
+
+#[test]
+fn window_parameter() {
+    let out = session(
+        1,
+        "fixtures/day_01/1.txt",
+        "2\nset window 1\n2\nreload\n2\n",
+    );
+    assert_eq!(answers(&out), ["Part 2: 5", "Part 2: 7", "Part 2: 7"]);
+}
+
+#[test]
+fn views_show_intermediate_structures() {
+    let out = session(
+        14,
+        "fixtures/day_14/1.txt",
+        "show\nshow pairs 1\nshow nope\n",
+    );
+    assert!(out.starts_with("Views: pairs\n"));
+    assert!(out.contains("CH: 1\nCN: 1\nHB: 1\nNB: 1\nNC: 1\nB: 2\nC: 2\nH: 1\nN: 2\n"));
+    assert!(out.contains("Error: No view \"nope\", expected one of: pairs"));
+
+    let out = session(13, "fixtures/day_13/1.txt", "show paper 1\n");
+    assert!(out.starts_with("17 dots\n"));
+
+    let out = session(16, "fixtures/day_16/8.txt", "show packets\n");
+    assert_eq!(out.lines().count(), 3);
+    assert!(out.starts_with("Sum = 3 "));
+}
+
+#[test]
+fn commands_after_errors_and_quit() {
+    let out = session(
+        14,
+        "fixtures/day_14/1.txt",
+        "frobnicate\nday 99\nday 13 fixtures/day_13/1.txt\nparams\n1\nquit\n2\n",
+    );
+    assert!(out.contains("Error: Unknown command \"frobnicate\", try help"));
+    assert!(out.contains("Day 13 has no parameters"));
+    assert_eq!(answers(&out), ["Part 1: 17"]);
+}
@@ -0,0 +1,186 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+
+use serde_json::{json, Value};
+
+use aoc2021::isolate::Isolation;
+use aoc2021::prelude::*;
+use aoc2021::serve::Server;
+
+// Start a server on a free local port, left running until the tests exit
+fn start() -> SocketAddr {
+    start_with(RunOptions::default())
+}
+
+fn start_with(options: RunOptions) -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0", options).unwrap();
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+    addr
+}
+
+// Send a request and return the response status and JSON body
+fn request(addr: SocketAddr, method: &str, target: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        target,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn solves_posted_input() {
+    let addr = start();
+    let input = include_str!("../fixtures/day_14/1.txt");
+
+    let (status, body) = request(addr, "POST", "/solve?day=14", input);
+    assert_eq!(status, 200);
+    assert_eq!(body["day"], 14);
+    assert_eq!(body["parts"][0]["value"], 1588);
+    assert_eq!(body["parts"][1]["value"], 2188189693529u64);
+    assert!(body["parts"][1]["time_ns"].is_u64());
+
+    let (status, body) = request(addr, "POST", "/solve?day=14&part=2", input);
+    assert_eq!(status, 200);
+    assert_eq!(body["parts"].as_array().unwrap().len(), 1);
+    assert_eq!(body["parts"][0]["part"], 2);
+}
+
+#[test]
+fn reports_structured_errors() {
+    let addr = start();
+
+    let (status, body) = request(
+        addr,
+        "POST",
+        "/solve?day=14&strict=1",
+        "NNCB\n\nCH -> B\nHH\n",
+    );
+    assert_eq!(status, 422);
+    assert_eq!(body["error"]["kind"], "parse_error");
+    assert_eq!(
+        (&body["error"]["line"], &body["error"]["column"]),
+        (&json!(4), &json!(3))
+    );
+
+    let (status, body) = request(addr, "POST", "/solve?day=99", "");
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (404, &json!("unknown_day"))
+    );
+    let (status, body) = request(addr, "POST", "/solve?day=14&part=3", "");
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (400, &json!("bad_request"))
+    );
+    let (status, body) = request(addr, "POST", "/solve", "");
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (400, &json!("bad_request"))
+    );
+    let (status, body) = request(addr, "GET", "/solve?day=14", "");
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (405, &json!("method_not_allowed"))
+    );
+    let (status, body) = request(addr, "POST", "/nope", "");
+    assert_eq!((status, &body["error"]["kind"]), (404, &json!("not_found")));
+}
+
+#[test]
+fn lists_days() {
+    let addr = start();
+    let (status, body) = request(addr, "GET", "/days", "");
+    assert_eq!(status, 200);
+    assert!(body["days"].as_array().unwrap().contains(&json!(14)));
+}
+
+#[test]
+fn decodes_query_values() {
+    let addr = start();
+    let input = include_str!("../fixtures/day_14/1.txt");
+    let (status, body) = request(addr, "POST", "/solve?d%61y=1%34&part=%32", input);
+    assert_eq!(status, 200);
+    assert_eq!(body["parts"][0]["value"], 2188189693529u64);
+
+    for target in ["/solve?day=1%4", "/solve?day=1%+4"] {
+        let (status, body) = request(addr, "POST", target, input);
+        assert_eq!(
+            (status, &body["error"]["kind"]),
+            (400, &json!("bad_request"))
+        );
+    }
+}
+
+// Start a server that solves in child processes, as the CLI does
+fn start_isolated(max_memory: Option<u64>) -> SocketAddr {
+    start_with(RunOptions {
+        isolation: Some(Isolation {
+            exe: PathBuf::from(env!("CARGO_BIN_EXE_aoc2021")),
+            max_memory,
+            max_cpu: None,
+        }),
+        ..RunOptions::default()
+    })
+}
+
+#[test]
+fn isolated_errors_keep_their_kind() {
+    let addr = start_isolated(None);
+
+    let (status, body) = request(addr, "POST", "/solve?day=99", "");
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (404, &json!("unknown_day"))
+    );
+    let (status, body) = request(addr, "POST", "/solve?day=17&algo=nope", "");
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (400, &json!("unknown_algorithm"))
+    );
+    let (status, body) = request(
+        addr,
+        "POST",
+        "/solve?day=14&strict=1",
+        "NNCB\n\nCH -> B\nHH\n",
+    );
+    assert_eq!(status, 422);
+    assert_eq!(body["error"]["kind"], "parse_error");
+    assert_eq!(
+        (&body["error"]["line"], &body["error"]["column"]),
+        (&json!(4), &json!(3))
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn survives_crashed_days() {
+    // Day 3 keeps every line, so the child runs out of memory and aborts
+    let addr = start_isolated(Some(16 << 20));
+    let input = "0101010101\n".repeat(1 << 20);
+    let (status, body) = request(addr, "POST", "/solve?day=3", &input);
+    assert_eq!(status, 200);
+    assert_eq!(body["parts"][0]["kind"], "Error");
+    assert!(body["parts"][0]["value"]
+        .as_str()
+        .unwrap()
+        .starts_with("Crashed"));
+
+    // Answers keep their kind through the child
+    let input = include_str!("../fixtures/day_14/1.txt");
+    let (status, body) = request(addr, "POST", "/solve?day=14&part=1", input);
+    assert_eq!(status, 200);
+    assert_eq!(body["parts"][0]["value"], 1588);
+}
@@ -0,0 +1,41 @@
+use std::env;
+use std::fs;
+
+use aoc2021::output::write_watch_run;
+use aoc2021::prelude::*;
+use aoc2021::solutions::solve_str;
+use aoc2021::watch::InputWatcher;
+
+#[test]
+fn poll_reports_changed_contents() {
+    let path = env::temp_dir().join(format!("aoc2021_watch_{}.txt", std::process::id()));
+    let mut watcher = InputWatcher::new(&path);
+    let missing = watcher.poll().unwrap().is_none();
+    fs::write(&path, "1\n").unwrap();
+    let first = watcher.poll().unwrap().map(<[u8]>::to_vec);
+    let unchanged = watcher.poll().unwrap().is_none();
+    // A different length, since the modification time may not change between quick writes
+    fs::write(&path, "12\n").unwrap();
+    let second = watcher.poll().unwrap().map(<[u8]>::to_vec);
+    fs::remove_file(&path).unwrap();
+
+    assert!(missing);
+    assert_eq!(first.as_deref(), Some(&b"1\n"[..]));
+    assert!(unchanged);
+    assert_eq!(second.as_deref(), Some(&b"12\n"[..]));
+}
+
+#[test]
+fn answers_diff_against_previous_run() {
+    let options = RunOptions::default();
+    let previous = solve_str(17, "target area: x=20..30, y=-10..-5", &options).unwrap();
+    let current = Ok(solve_str(17, "target area: x=20..30, y=-11..-5", &options).unwrap());
+    let mut out = Vec::new();
+    write_watch_run(&mut out, 17, &current, Some(&previous)).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("Day 17 Part 1: 55 (was 45)\nDay 17 Part 2: 130 (was 112)\n"));
+
+    let mut out = Vec::new();
+    write_watch_run(&mut out, 17, &current, current.as_ref().ok()).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("55 (unchanged)"));
+}
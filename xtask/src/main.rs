@@ -0,0 +1,153 @@
+//! Development tasks that write to the source tree, which build scripts must not do.
+//!
+//! - `cargo xtask header [--check]` regenerates the C header `include/aoc2021.h` from
+//!   `src/ffi.rs`, or only checks it's up to date. The header is checked in, so run it after
+//!   changing the C API.
+//! - `cargo xtask cdylib [--release]` builds the C API as a shared library in `target/`, which
+//!   plain `cargo build` doesn't.
+//! - `cargo xtask fmt [--check]` runs `cargo fmt` and also formats the day modules, which are
+//!   declared by build.rs where `cargo fmt` can't find them.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+
+use cbindgen::{Config, EnumConfig, Language, RenameRule};
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let args: Vec<_> = args.iter().map(String::as_str).collect();
+    let ok = match args[..] {
+        ["header"] => header(false),
+        ["header", "--check"] => header(true),
+        ["cdylib"] => cdylib(false),
+        ["cdylib", "--release"] => cdylib(true),
+        ["fmt"] => fmt(false),
+        ["fmt", "--check"] => fmt(true),
+        _ => {
+            eprintln!(
+                "Usage: cargo xtask header [--check] | cargo xtask cdylib [--release] | \
+                 cargo xtask fmt [--check]"
+            );
+            exit(2);
+        }
+    };
+    if !ok {
+        exit(1);
+    }
+}
+
+fn root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+fn cargo() -> Command {
+    Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+}
+
+fn header_path() -> PathBuf {
+    root().join("include").join("aoc2021.h")
+}
+
+// Write the C header into `include/`, where C code can find it, or only check it's up to date
+fn header(check: bool) -> bool {
+    let header = generate_header();
+    if !check {
+        fs::write(header_path(), header).unwrap();
+        return true;
+    }
+    let current = fs::read(header_path()).unwrap_or_default() == header;
+    if !current {
+        eprintln!("include/aoc2021.h is out of date, run `cargo xtask header`");
+    }
+    current
+}
+
+// Generate the C header for the `ffi` module
+fn generate_header() -> Vec<u8> {
+    let root = root();
+    let mut config = Config::default();
+    config.language = Language::C;
+    config.header = Some("/* C API for the aoc2021 solvers, see src/ffi.rs */".into());
+    config.autogen_warning =
+        Some("/* Generated by `cargo xtask header`, don't edit by hand */".into());
+    config.include_guard = Some("AOC2021_H".into());
+    config.cpp_compat = true;
+    config.usize_is_size_t = true;
+    config.enumeration = EnumConfig {
+        rename_variants: RenameRule::QualifiedScreamingSnakeCase,
+        ..EnumConfig::default()
+    };
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(root.join("src").join("ffi.rs"))
+        .generate()
+        .unwrap()
+        .write(&mut header);
+    header
+}
+
+// Build the `ffi` feature as a shared library. The crate type is only added here, so other builds
+// don't pay for linking it.
+fn cdylib(release: bool) -> bool {
+    let mut cmd = cargo();
+    cmd.current_dir(root()).args([
+        "rustc",
+        "--lib",
+        "--features",
+        "ffi",
+        "--crate-type",
+        "cdylib",
+    ]);
+    if release {
+        cmd.arg("--release");
+    }
+    cmd.status().is_ok_and(|status| status.success())
+}
+
+// Format the workspace and the day modules, or only check they're formatted
+fn fmt(check: bool) -> bool {
+    let root = root();
+    let mut cargo_fmt = cargo();
+    cargo_fmt.current_dir(root).args(["fmt", "--all"]);
+    if check {
+        cargo_fmt.args(["--", "--check"]);
+    }
+
+    let solutions_dir = root.join("src").join("solutions");
+    let mut days: Vec<PathBuf> = fs::read_dir(&solutions_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with("day_") && name.ends_with(".rs")
+        })
+        .collect();
+    days.sort();
+    let mut rustfmt = Command::new("rustfmt");
+    rustfmt.current_dir(root).args(["--edition", "2021"]);
+    if check {
+        rustfmt.arg("--check");
+    }
+    rustfmt.args(&days);
+
+    // Run both, so every unformatted file is reported
+    let failed = [cargo_fmt, rustfmt]
+        .iter_mut()
+        .map(|cmd| cmd.status().is_ok_and(|status| status.success()))
+        .filter(|ok| !ok)
+        .count();
+    failed == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn header_is_current() {
+        assert!(header(true), "run `cargo xtask header`");
+    }
+}